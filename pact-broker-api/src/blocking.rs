@@ -0,0 +1,300 @@
+//! A blocking Pact Broker API client, mirroring [`client::BrokerClient`](crate::client::BrokerClient)
+//! for programs without an async runtime.
+//!
+//! Each client drives the requests on its own single-threaded tokio runtime,
+//! it must not be used from within an async runtime, as `reqwest::blocking`.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let api = pact_broker_api::client::Builder::new()
+//!         .base_url("http://my-pact-broker")?
+//!         .basic_auth("username", "Pa55w0rd")
+//!         .build_blocking()?;
+//!     let pacts = api.pacts().latest()?;
+//! #    Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use pact_broker_models::{
+    contract::Contract,
+    hal::Link,
+    index::Index,
+    pacticipants::{NewVersion, Pacticipants, Tag, Version},
+    pacts::{PactVersions, Pacts},
+    publish::{PublishContracts, PublishResults, Verification, VerificationResult},
+    webhooks::{Execution, TriggeredWebhook, Webhook},
+};
+use serde::Serialize;
+use snafu::ResultExt;
+use url::Url;
+
+use crate::client::{self, error, FromResponse, Result};
+
+/// The blocking Pact Broker API client.
+#[derive(Debug, Clone)]
+pub struct BrokerClient {
+    inner: client::BrokerClient,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl BrokerClient {
+    /// Wrap an async `client`, starting the runtime driving its requests.
+    pub fn new(client: client::BrokerClient) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context(error::RuntimeSnafu)?;
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client, sharing the connections and the broker index.
+    pub fn inner(&self) -> &client::BrokerClient {
+        &self.inner
+    }
+}
+
+/// # Pact Broker API Methods
+impl BrokerClient {
+    /// Create a new [`PactsHandler`].
+    pub fn pacts(&self) -> PactsHandler<'_> {
+        PactsHandler { client: self }
+    }
+
+    /// Create a new [`PacticipantsHandler`].
+    pub fn pacticipants(&self) -> PacticipantsHandler<'_> {
+        PacticipantsHandler { client: self }
+    }
+
+    /// Create a new [`WebhooksHandler`].
+    pub fn webhooks(&self) -> WebhooksHandler<'_> {
+        WebhooksHandler { client: self }
+    }
+
+    /// See [`client::BrokerClient::index`].
+    pub fn index(&self) -> Result<Arc<Index>> {
+        self.runtime.block_on(self.inner.index())
+    }
+
+    /// See [`client::BrokerClient::relation`].
+    pub fn relation(&self, rel: &str) -> Result<Link> {
+        self.runtime.block_on(self.inner.relation(rel))
+    }
+
+    /// See [`client::BrokerClient::root_url`].
+    pub fn root_url(&self) -> Result<Url> {
+        self.runtime.block_on(self.inner.root_url())
+    }
+}
+
+/// # HTTP Methods
+impl BrokerClient {
+    /// See [`client::BrokerClient::get`].
+    pub fn get<A, P, R>(
+        &self,
+        route: A,
+        parameters: Option<&P>,
+        headers: Option<reqwest::header::HeaderMap>,
+    ) -> Result<R>
+    where
+        A: AsRef<str>,
+        P: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        self.runtime
+            .block_on(self.inner.get(route, parameters, headers))
+    }
+
+    /// See [`client::BrokerClient::post`].
+    pub fn post<A, B, R>(&self, route: A, body: Option<&B>) -> Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        self.runtime.block_on(self.inner.post(route, body))
+    }
+
+    /// See [`client::BrokerClient::put`].
+    pub fn put<A, B, R>(&self, route: A, body: Option<&B>) -> Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        self.runtime.block_on(self.inner.put(route, body))
+    }
+
+    /// See [`client::BrokerClient::follow`].
+    pub fn follow<R>(&self, link: &Link) -> Result<R>
+    where
+        R: FromResponse,
+    {
+        self.runtime.block_on(self.inner.follow(link))
+    }
+
+    /// See [`client::BrokerClient::batch_get`].
+    pub fn batch_get<R>(
+        &self,
+        urls: Vec<Url>,
+        headers: Option<reqwest::header::HeaderMap>,
+    ) -> Result<Vec<R>>
+    where
+        R: FromResponse,
+    {
+        self.runtime.block_on(self.inner.batch_get(urls, headers))
+    }
+}
+
+/// Blocking mirror of [`client::PactsHandler`](crate::client::PactsHandler).
+pub struct PactsHandler<'client> {
+    client: &'client BrokerClient,
+}
+
+impl<'client> PactsHandler<'client> {
+    pub fn latest(&self) -> Result<Pacts> {
+        let client = self.client;
+        client.runtime.block_on(client.inner.pacts().latest())
+    }
+
+    pub fn contract(
+        &self,
+        provider: &str,
+        consumer: &str,
+        version: Option<&str>,
+    ) -> Result<Contract> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().contract(provider, consumer, version))
+    }
+
+    pub fn versions(&self, contract: &Contract) -> Result<PactVersions> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().versions(contract))
+    }
+
+    pub fn latest_verification(&self, contract: &Contract) -> Result<Option<Verification>> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().latest_verification(contract))
+    }
+
+    pub fn previous_distinct(&self, contract: &Contract) -> Result<Contract> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().previous_distinct(contract))
+    }
+
+    pub fn diff_previous_distinct(&self, contract: &Contract) -> Result<String> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().diff_previous_distinct(contract))
+    }
+
+    pub fn publish(&self, contracts: &PublishContracts) -> Result<PublishResults> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().publish(contracts))
+    }
+
+    pub fn publish_verification(
+        &self,
+        contract: &Contract,
+        result: &VerificationResult,
+    ) -> Result<Verification> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.pacts().publish_verification(contract, result))
+    }
+}
+
+/// Blocking mirror of [`client::PacticipantsHandler`](crate::client::PacticipantsHandler).
+pub struct PacticipantsHandler<'client> {
+    client: &'client BrokerClient,
+}
+
+impl<'client> PacticipantsHandler<'client> {
+    pub fn list(&self) -> Result<Pacticipants> {
+        let client = self.client;
+        client.runtime.block_on(client.inner.pacticipants().list())
+    }
+
+    pub fn create_version(
+        &self,
+        pacticipant: &str,
+        version: &str,
+        attributes: &NewVersion,
+    ) -> Result<Version> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(
+                client
+                    .inner
+                    .pacticipants()
+                    .create_version(pacticipant, version, attributes),
+            )
+    }
+
+    pub fn tag_version(&self, pacticipant: &str, version: &str, tag: &str) -> Result<Tag> {
+        let client = self.client;
+        client.runtime.block_on(
+            client
+                .inner
+                .pacticipants()
+                .tag_version(pacticipant, version, tag),
+        )
+    }
+}
+
+/// Blocking mirror of [`client::WebhooksHandler`](crate::client::WebhooksHandler).
+pub struct WebhooksHandler<'client> {
+    client: &'client BrokerClient,
+}
+
+impl<'client> WebhooksHandler<'client> {
+    pub fn list(&self) -> Result<Vec<Webhook>> {
+        let client = self.client;
+        client.runtime.block_on(client.inner.webhooks().list())
+    }
+
+    pub fn for_pact(&self, contract: &Contract) -> Result<Vec<Webhook>> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.webhooks().for_pact(contract))
+    }
+
+    pub fn create(&self, webhook: &Webhook) -> Result<Webhook> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.webhooks().create(webhook))
+    }
+
+    pub fn execute(&self, webhook: &Webhook) -> Result<Execution> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.webhooks().execute(webhook))
+    }
+
+    pub fn triggered(&self, contract: &Contract) -> Result<Vec<TriggeredWebhook>> {
+        let client = self.client;
+        client
+            .runtime
+            .block_on(client.inner.webhooks().triggered(contract))
+    }
+}
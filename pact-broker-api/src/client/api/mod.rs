@@ -1,3 +1,19 @@
+use pact_broker_models::{contract::Contract, hal::Link};
+use snafu::OptionExt;
+
+use crate::client::{error, Result};
+
+mod pacticipants;
 mod pacts;
+mod webhooks;
 
+pub use pacticipants::PacticipantsHandler;
 pub use pacts::PactsHandler;
+pub use webhooks::WebhooksHandler;
+
+/// The link of the relation `rel` of `contract`, which must be fetched from the broker.
+fn contract_link<'a>(contract: &'a Contract, rel: &str) -> Result<&'a Link> {
+    contract
+        .link(rel)
+        .context(error::UnsupportedSnafu { relation: rel })
+}
@@ -0,0 +1,56 @@
+use pact_broker_models::index::Capability;
+use pact_broker_models::pacticipants::{NewVersion, Pacticipants, Tag, Version};
+
+use crate::client::BrokerClient;
+use crate::client::Result;
+
+pub struct PacticipantsHandler<'client> {
+    client: &'client BrokerClient,
+}
+
+impl<'client> PacticipantsHandler<'client> {
+    /// Creates a new [`PacticipantsHandler`].
+    pub(crate) fn new(client: &'client BrokerClient) -> Self {
+        Self { client }
+    }
+
+    /// Every consumer and provider known by the broker.
+    pub async fn list(&self) -> Result<Pacticipants> {
+        let link = self
+            .client
+            .relation(Capability::Pacticipants.relation())
+            .await?;
+        self.client.follow(&link).await
+    }
+
+    /// Create the `version` of `pacticipant`, or update its branch and build URL.
+    pub async fn create_version(
+        &self,
+        pacticipant: &str,
+        version: &str,
+        attributes: &NewVersion,
+    ) -> Result<Version> {
+        let link = self
+            .client
+            .relation("pb:pacticipant-version")
+            .await?
+            .expand(&[("pacticipant", pacticipant), ("version", version)]);
+        self.client.put(&link.href, Some(attributes)).await
+    }
+
+    /// Tag the `version` of `pacticipant`, creating the version when it does not exist.
+    pub async fn tag_version(&self, pacticipant: &str, version: &str, tag: &str) -> Result<Tag> {
+        let link = self
+            .client
+            .relation("pb:pacticipant-version-tag")
+            .await?
+            .expand(&[
+                ("pacticipant", pacticipant),
+                ("version", version),
+                ("tag", tag),
+            ]);
+        self.client
+            .put(&link.href, Some(&serde_json::json!({})))
+            .await
+    }
+}
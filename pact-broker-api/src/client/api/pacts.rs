@@ -1,8 +1,16 @@
 use pact_broker_models::contract::Contract;
-use pact_broker_models::pacts::Pacts;
+use pact_broker_models::index::Capability;
+use pact_broker_models::pacts::{PactVersions, Pacts, ProviderPacts};
+use pact_broker_models::publish::{
+    PublishContracts, PublishResults, Verification, VerificationResult,
+};
+use reqwest::StatusCode;
 
+use super::contract_link;
 use crate::client::BrokerClient;
+use crate::client::Error;
 use crate::client::Result;
+use crate::client::Text;
 
 pub struct PactsHandler<'client> {
     client: &'client BrokerClient,
@@ -15,17 +23,95 @@ impl<'client> PactsHandler<'client> {
     }
 
     pub async fn latest(&self) -> Result<Pacts> {
-        self.client.get("/pacts/latest", None::<&()>, None).await
+        let link = self
+            .client
+            .relation(Capability::LatestPacts.relation())
+            .await?;
+        self.client.follow(&link).await
     }
 
+    /// The contract between `consumer` and `provider`, of the consumer `version` or the latest one.
     pub async fn contract(
         &self,
         provider: &str,
         consumer: &str,
         version: Option<&str>,
     ) -> Result<Contract> {
-        let version = version.unwrap_or("latest");
-        let route = format!("/pacts/provider/{provider}/consumer/{consumer}/{version}");
-        self.client.get(route, None::<&()>, None).await
+        let link = match version {
+            // the pact published for a consumer version is served on the same resource
+            Some(version) => self.client.relation("pb:publish-pact").await?.expand(&[
+                ("provider", provider),
+                ("consumer", consumer),
+                ("consumerApplicationVersion", version),
+            ]),
+            None => {
+                let latest = self
+                    .client
+                    .relation("pb:latest-provider-pacts")
+                    .await?
+                    .expand(&[("provider", provider)]);
+                let pacts: ProviderPacts = self.client.follow(&latest).await?;
+                match pacts.pact(consumer) {
+                    Some(link) => link.clone(),
+                    None => {
+                        return Err(Error::NotFound {
+                            url: self.client.absolute_url(&latest.href)?,
+                            status: StatusCode::NOT_FOUND,
+                            body: format!("no pact between {consumer} and {provider}"),
+                        })
+                    }
+                }
+            }
+        };
+        self.client.follow(&link).await
+    }
+
+    /// Publish the contracts of a consumer version, creating the version with its branch and tags.
+    pub async fn publish(&self, contracts: &PublishContracts) -> Result<PublishResults> {
+        let link = self
+            .client
+            .relation(Capability::PublishContracts.relation())
+            .await?;
+        self.client.post(&link.href, Some(contracts)).await
+    }
+
+    /// Publish the result of the verification of `contract` by a provider version,
+    /// `contract` being fetched from the broker.
+    pub async fn publish_verification(
+        &self,
+        contract: &Contract,
+        result: &VerificationResult,
+    ) -> Result<Verification> {
+        let link = contract_link(contract, "pb:publish-verification-results")?;
+        self.client.post(&link.href, Some(result)).await
+    }
+
+    /// The latest verification result of `contract`, `None` when the provider never verified it.
+    pub async fn latest_verification(&self, contract: &Contract) -> Result<Option<Verification>> {
+        let link = contract_link(contract, "pb:latest-verification-results")?;
+        match self.client.follow(link).await {
+            Ok(verification) => Ok(Some(verification)),
+            Err(Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// All the versions of the pact between the consumer and the provider of `contract`.
+    pub async fn versions(&self, contract: &Contract) -> Result<PactVersions> {
+        let link = contract_link(contract, "pb:all-pact-versions")?;
+        self.client.follow(link).await
+    }
+
+    /// The previous version of `contract` with a different content.
+    pub async fn previous_distinct(&self, contract: &Contract) -> Result<Contract> {
+        let link = contract_link(contract, "pb:previous-distinct")?;
+        self.client.follow(link).await
+    }
+
+    /// Diff of the content of `contract` with its previous distinct version, as computed by the broker.
+    pub async fn diff_previous_distinct(&self, contract: &Contract) -> Result<String> {
+        let link = contract_link(contract, "pb:diff-previous-distinct")?;
+        let Text(diff) = self.client.follow(link).await?;
+        Ok(diff)
     }
 }
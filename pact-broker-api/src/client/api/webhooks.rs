@@ -0,0 +1,73 @@
+use futures::{stream, StreamExt, TryStreamExt};
+use pact_broker_models::contract::Contract;
+use pact_broker_models::index::Capability;
+use pact_broker_models::webhooks::{
+    Execution, TriggeredWebhook, TriggeredWebhooks, Webhook, Webhooks,
+};
+use snafu::OptionExt;
+
+use super::contract_link;
+use crate::client::error;
+use crate::client::Result;
+use crate::client::{BrokerClient, MAX_CONCURRENT_REQUESTS};
+
+pub struct WebhooksHandler<'client> {
+    client: &'client BrokerClient,
+}
+
+impl<'client> WebhooksHandler<'client> {
+    /// Creates a new [`WebhooksHandler`].
+    pub(crate) fn new(client: &'client BrokerClient) -> Self {
+        Self { client }
+    }
+
+    /// All the webhooks of the broker.
+    pub async fn list(&self) -> Result<Vec<Webhook>> {
+        let link = self
+            .client
+            .relation(Capability::Webhooks.relation())
+            .await?;
+        self.fetch(&self.client.follow(&link).await?).await
+    }
+
+    /// Webhooks applying to the pact between the consumer and the provider of `contract`,
+    /// `contract` being fetched from the broker.
+    pub async fn for_pact(&self, contract: &Contract) -> Result<Vec<Webhook>> {
+        let link = contract_link(contract, "pb:pact-webhooks")?;
+        self.fetch(&self.client.follow(link).await?).await
+    }
+
+    /// Create a webhook, applying to all the pacts when it has no consumer nor provider.
+    pub async fn create(&self, webhook: &Webhook) -> Result<Webhook> {
+        let link = self
+            .client
+            .relation(Capability::Webhooks.relation())
+            .await?;
+        self.client.post(&link.href, Some(webhook)).await
+    }
+
+    /// Execute `webhook` to test it, whatever its events.
+    pub async fn execute(&self, webhook: &Webhook) -> Result<Execution> {
+        let rel = "pb:execute";
+        let link = webhook
+            .links
+            .get(rel)
+            .context(error::UnsupportedSnafu { relation: rel })?;
+        self.client.post(&link.href, None::<&()>).await
+    }
+
+    /// Webhooks triggered by the publication of `contract`, and their executions.
+    pub async fn triggered(&self, contract: &Contract) -> Result<Vec<TriggeredWebhook>> {
+        let link = contract_link(contract, "pb:triggered-webhooks")?;
+        let triggered: TriggeredWebhooks = self.client.follow(link).await?;
+        Ok(triggered.embedded.triggered_webhooks)
+    }
+
+    async fn fetch(&self, webhooks: &Webhooks) -> Result<Vec<Webhook>> {
+        stream::iter(webhooks.webhooks())
+            .map(|link| self.client.follow(link))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+}
@@ -1,12 +1,416 @@
-#[derive(Default, Debug, Clone, PartialEq)]
-
-/// Authentication used in `BrockerClient`
-pub enum Auth {
-    /// No Authentication.
-    #[default]
-    None,
-    /// Basic Auth HTTP Header to be set to provide authentication
-    Basic { username: String, password: String },
-    /// Set the HTTP Header authorization
-    Token { token: String },
+use std::{
+    fmt,
+    future::Future,
+    process::Command,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{error, Result};
+use futures::channel::oneshot;
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use snafu::ResultExt;
+use url::Url;
+
+/// Delay before the real expiration at which a cached token is considered stale.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Authentication used in `BrokerClient`.
+///
+/// A provider decorates every request sent to the broker with its credentials,
+/// fetching or refreshing them when needed.
+#[async_trait::async_trait]
+pub trait AuthProvider: fmt::Debug + Send + Sync {
+    /// Add the credentials to `request`.
+    /// `client` may be used by the provider to fetch a token.
+    async fn authenticate(
+        &self,
+        client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder>;
+
+    /// Drop any cached credentials, called when the broker answered `401 Unauthorized`.
+    /// Returns `true` when a new attempt may succeed with fresh credentials.
+    fn invalidate(&self) -> bool {
+        false
+    }
+}
+
+/// No Authentication.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NoAuth;
+
+#[async_trait::async_trait]
+impl AuthProvider for NoAuth {
+    async fn authenticate(
+        &self,
+        _client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        Ok(request)
+    }
+}
+
+/// Basic Auth HTTP Header to be set to provide authentication.
+#[derive(Clone, PartialEq)]
+pub struct BasicAuth {
+    username: String,
+    password: String,
+}
+
+impl BasicAuth {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+}
+
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for BasicAuth {
+    async fn authenticate(
+        &self,
+        _client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        Ok(request.basic_auth(&self.username, Some(&self.password)))
+    }
+}
+
+/// Static bearer token set in the HTTP Header authorization.
+#[derive(Clone, PartialEq)]
+pub struct BearerToken {
+    token: String,
+}
+
+impl BearerToken {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_owned(),
+        }
+    }
+}
+
+impl fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerToken").finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for BearerToken {
+    async fn authenticate(
+        &self,
+        _client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        Ok(request.bearer_auth(&self.token))
+    }
+}
+
+/// A token kept in memory until it expires.
+#[derive(Clone)]
+struct CachedToken {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_valid(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => Instant::now() + EXPIRY_MARGIN < expires_at,
+            None => true,
+        }
+    }
+}
+
+/// The token of a provider, fetched by a single request at a time.
+#[derive(Default)]
+struct TokenCache {
+    token: Mutex<Option<CachedToken>>,
+    /// held while a token is fetched, so that concurrent requests wait for it
+    fetching: futures::lock::Mutex<()>,
+}
+
+impl TokenCache {
+    fn get(&self) -> Option<String> {
+        let cache = self.token.lock().unwrap();
+        cache
+            .as_ref()
+            .filter(|token| token.is_valid())
+            .map(|token| token.value.clone())
+    }
+
+    /// The cached token, or the one returned with its time to live by `fetch`
+    /// when there is none. `fetch` is called once for the requests waiting on it.
+    async fn get_or_fetch<F, Fut>(&self, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(String, Option<Duration>)>>,
+    {
+        if let Some(token) = self.get() {
+            return Ok(token);
+        }
+        let _fetching = self.fetching.lock().await;
+        // fetched by another request while waiting
+        if let Some(token) = self.get() {
+            return Ok(token);
+        }
+        let (value, ttl) = fetch().await?;
+        *self.token.lock().unwrap() = Some(CachedToken {
+            value: value.clone(),
+            expires_at: ttl.map(|ttl| Instant::now() + ttl),
+        });
+        Ok(value)
+    }
+
+    fn clear(&self) {
+        *self.token.lock().unwrap() = None;
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// OAuth2 client credentials grant.
+///
+/// The access token is requested from `token_url` on first use and
+/// requested again once it expired or has been rejected by the broker.
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let auth = pact_broker_api::client::OAuth2ClientCredentials::new(
+///         "https://sso.example.com/oauth2/token".parse()?,
+///         "pact-graph-network",
+///         "s3cr3t",
+///     )
+///     .with_scope("pactflow");
+///     let api = pact_broker_api::client::Builder::new()
+///         .base_url("http://my-pact-broker")?
+///         .auth(auth)
+///         .build()?;
+/// #    Ok(())
+/// # }
+/// ```
+pub struct OAuth2ClientCredentials {
+    token_url: Url,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    cache: TokenCache,
+}
+
+impl OAuth2ClientCredentials {
+    pub fn new(token_url: Url, client_id: &str, client_secret: &str) -> Self {
+        Self {
+            token_url,
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            scope: None,
+            cache: TokenCache::default(),
+        }
+    }
+
+    /// Request the token for the given `scope`.
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_owned());
+        self
+    }
+
+    async fn fetch_token(&self, client: &reqwest::Client) -> Result<(String, Option<Duration>)> {
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scope) = &self.scope {
+            form.push(("scope", scope.as_str()));
+        }
+
+        let response = client
+            .post(self.token_url.clone())
+            .form(&form)
+            .send()
+            .await
+            .context(error::HttpSnafu)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(error::Error::Auth {
+                message: format!("token endpoint {} answered {}", self.token_url, status),
+            });
+        }
+        let token: TokenResponse = response.json().await.context(error::HttpSnafu)?;
+        Ok((
+            token.access_token,
+            token.expires_in.map(Duration::from_secs),
+        ))
+    }
+}
+
+impl fmt::Debug for OAuth2ClientCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuth2ClientCredentials")
+            .field("token_url", &self.token_url.as_str())
+            .field("client_id", &self.client_id)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for OAuth2ClientCredentials {
+    async fn authenticate(
+        &self,
+        client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        let token = self.cache.get_or_fetch(|| self.fetch_token(client)).await?;
+        Ok(request.bearer_auth(token))
+    }
+
+    fn invalidate(&self) -> bool {
+        self.cache.clear();
+        true
+    }
+}
+
+/// Bearer token printed on the standard output of a command,
+/// e.g. `vault read -field=token secret/pact-broker`.
+///
+/// The command is run again once `ttl` elapsed, or when the broker rejected the token.
+pub struct CommandToken {
+    program: String,
+    args: Vec<String>,
+    ttl: Option<Duration>,
+    cache: TokenCache,
+}
+
+impl CommandToken {
+    pub fn new<I, S>(program: &str, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            program: program.to_owned(),
+            args: args.into_iter().map(Into::into).collect(),
+            ttl: None,
+            cache: TokenCache::default(),
+        }
+    }
+
+    /// Run a shell `command` line through `sh -c`.
+    pub fn shell(command: &str) -> Self {
+        Self::new("sh", ["-c", command])
+    }
+
+    /// Keep the token for `ttl` before running the command again.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    async fn fetch_token(&self) -> Result<(String, Option<Duration>)> {
+        // run on a thread of its own, not to block the executor while the command runs
+        let (sender, receiver) = oneshot::channel();
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        thread::spawn(move || sender.send(command.output()));
+        let output = receiver
+            .await
+            .map_err(|_| error::Error::Auth {
+                message: format!("`{}` was interrupted", self.program),
+            })?
+            .map_err(|e| error::Error::Auth {
+                message: format!("could not run `{}`: {}", self.program, e),
+            })?;
+        if !output.status.success() {
+            return Err(error::Error::Auth {
+                message: format!(
+                    "`{}` exited with {}: {}",
+                    self.program,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        if token.is_empty() {
+            return Err(error::Error::Auth {
+                message: format!("`{}` printed an empty token", self.program),
+            });
+        }
+        Ok((token, self.ttl))
+    }
+}
+
+impl fmt::Debug for CommandToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandToken")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for CommandToken {
+    async fn authenticate(
+        &self,
+        _client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<RequestBuilder> {
+        let token = self.cache.get_or_fetch(|| self.fetch_token()).await?;
+        Ok(request.bearer_auth(token))
+    }
+
+    fn invalidate(&self) -> bool {
+        self.cache.clear();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthProvider, CommandToken};
+
+    #[tokio::test]
+    async fn should_read_token_from_command() {
+        let client = reqwest::Client::new();
+        let auth = CommandToken::shell("echo '  s3cr3t  '");
+        let request = auth
+            .authenticate(&client, client.get("http://localhost"))
+            .await
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.headers()[reqwest::header::AUTHORIZATION],
+            "Bearer s3cr3t"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_command_fails() {
+        let client = reqwest::Client::new();
+        let auth = CommandToken::shell("exit 3");
+        let got = auth
+            .authenticate(&client, client.get("http://localhost"))
+            .await;
+        assert!(got.is_err());
+    }
 }
@@ -1,30 +1,85 @@
-use super::{api, auth::Auth, error, from_response::FromResponse, Result};
+use super::{api, auth::AuthProvider, error, from_response::FromResponse, Result};
 use futures::{stream, StreamExt};
 use log::error;
+use pact_broker_models::{hal::Link, index::Index};
 use reqwest::StatusCode;
 use serde::Serialize;
-use snafu::ResultExt;
-use std::{result::Result::Ok, sync::mpsc};
+use snafu::{OptionExt, ResultExt};
+use std::{
+    result::Result::Ok,
+    sync::{mpsc, Arc, Mutex},
+};
 #[cfg(doctest)]
 use tokio_test;
 use url::Url;
 
 const MAX_RETRIES: u32 = 3;
 
+/// Maximum number of requests sent at once by [`BrokerClient::batch_get`],
+/// and by the handlers fetching several resources.
+pub const MAX_CONCURRENT_REQUESTS: usize = 100;
+
 /// The Pact Brocker API client.
 #[derive(Debug, Clone)]
 pub struct BrokerClient {
     pub(crate) client: reqwest::Client,
     pub base_url: Url,
-    pub(crate) auth: Auth,
+    pub(crate) auth: Arc<dyn AuthProvider>,
+    /// Index of the broker, fetched on first use
+    pub(crate) index: Arc<Mutex<Option<Arc<Index>>>>,
 }
 
 /// # Pact Broker API Methods
 impl BrokerClient {
     /// Create a new [`api::PactsHandler`].
-    pub fn pacts(&self) -> api::PactsHandler {
+    pub fn pacts(&self) -> api::PactsHandler<'_> {
         api::PactsHandler::new(self)
     }
+
+    /// Create a new [`api::PacticipantsHandler`].
+    pub fn pacticipants(&self) -> api::PacticipantsHandler<'_> {
+        api::PacticipantsHandler::new(self)
+    }
+
+    /// Create a new [`api::WebhooksHandler`].
+    pub fn webhooks(&self) -> api::WebhooksHandler<'_> {
+        api::WebhooksHandler::new(self)
+    }
+
+    /// The index of the broker, served on the `base_url`.
+    /// It is fetched once, and then shared by the clones of the client.
+    pub async fn index(&self) -> Result<Arc<Index>> {
+        if let Some(index) = self.index.lock().unwrap().as_ref() {
+            return Ok(index.clone());
+        }
+        let index: Arc<Index> =
+            Arc::new(self.get(self.base_url.as_str(), None::<&()>, None).await?);
+        *self.index.lock().unwrap() = Some(index.clone());
+        Ok(index)
+    }
+
+    /// The link of the relation `rel` of the broker index, e.g. `pb:latest-pact-versions`,
+    /// or an [`Unsupported`](error::Error::Unsupported) error when the broker lacks it.
+    pub async fn relation(&self, rel: &str) -> Result<Link> {
+        self.index()
+            .await?
+            .relation(rel)
+            .cloned()
+            .context(error::UnsupportedSnafu { relation: rel })
+    }
+
+    /// Root of the broker resources, i.e. the `self` link of the index,
+    /// including the sub-path the broker is mounted under.
+    pub async fn root_url(&self) -> Result<Url> {
+        let mut root = match self.index().await?.relation("self") {
+            Some(link) => self.absolute_url(&link.href)?,
+            None => self.base_url.clone(),
+        };
+        if !root.path().ends_with('/') {
+            root.set_path(&format!("{}/", root.path()));
+        }
+        Ok(root)
+    }
 }
 
 /// # HTTP Methods
@@ -59,6 +114,59 @@ impl BrokerClient {
         R::from_response(response).await
     }
 
+    /// Send a `POST` request with an optional JSON body,
+    /// returning the body of the response.
+    pub async fn post<A, B, R>(&self, route: A, body: Option<&B>) -> Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        let url = self.absolute_url(route)?;
+        let mut request = self.client.post(url);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = self.execute(request).await?;
+        R::from_response(response).await
+    }
+
+    /// Send a `PUT` request with an optional JSON body,
+    /// returning the body of the response.
+    pub async fn put<A, B, R>(&self, route: A, body: Option<&B>) -> Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        let url = self.absolute_url(route)?;
+        let mut request = self.client.put(url);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = self.execute(request).await?;
+        R::from_response(response).await
+    }
+
+    /// Send a `GET` request to the `href` of a HAL `link`, e.g. a relation of
+    /// [`Contract::links`](pact_broker_models::contract::Contract::links),
+    /// returning the body of the response.
+    /// A templated link must be expanded first with [`Link::expand`].
+    pub async fn follow<R>(&self, link: &Link) -> Result<R>
+    where
+        R: FromResponse,
+    {
+        snafu::ensure!(
+            !link.is_templated(),
+            error::TemplatedLinkSnafu {
+                href: link.href.clone()
+            }
+        );
+        self.get(&link.href, None::<&()>, None).await
+    }
+
     /// Send multiple `GET` requests with optional header
     /// returning a vector of responses.
     pub async fn batch_get<R>(
@@ -83,7 +191,7 @@ impl BrokerClient {
                     R::from_response(response).await
                 }
             })
-            .buffer_unordered(100);
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS);
 
         let (tx, rx) = mpsc::channel::<R>();
 
@@ -114,29 +222,26 @@ impl BrokerClient {
     }
 
     /// Execute given `request` given `BrokerClient`.
-    pub async fn execute(&self, mut request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    /// The request is authenticated by the configured [`AuthProvider`], and sent again
+    /// with fresh credentials when the broker answered `401 Unauthorized`.
+    pub async fn execute(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
         let mut retries = 0;
+        let mut request = request;
         loop {
-            let mut retry_request = None;
-            match &self.auth {
-                Auth::None => (),
-                Auth::Basic { username, password } => {
-                    retry_request = Some(request.try_clone().unwrap());
-                    request = request.basic_auth(username, Some(password));
-                }
-                Auth::Token { token } => {
-                    retry_request = Some(request.try_clone().unwrap());
-                    request = request.bearer_auth(token);
-                }
-            }
-            let result = request.send().await;
+            let retry_request = request.try_clone();
+            let result = self
+                .auth
+                .authenticate(&self.client, request)
+                .await?
+                .send()
+                .await;
             let status = match &result {
                 Ok(v) => Some(v.status()),
                 Err(e) => e.status(),
             };
             if let Some(StatusCode::UNAUTHORIZED) = status {
                 if let Some(retry) = retry_request {
-                    if retries < MAX_RETRIES {
+                    if retries < MAX_RETRIES && self.auth.invalidate() {
                         retries += 1;
                         request = retry;
                         continue;
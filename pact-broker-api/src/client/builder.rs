@@ -1,6 +1,9 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use super::{auth::Auth, error, BrokerClient, Result};
+use super::{
+    auth::{AuthProvider, BasicAuth, BearerToken, NoAuth},
+    error, BrokerClient, Result,
+};
 use reqwest::header::HeaderName;
 use snafu::ResultExt;
 use url::Url;
@@ -20,7 +23,7 @@ pub struct Builder {
     base_url: Option<Url>,
     extra_headers: Vec<(HeaderName, String)>,
     timeout: Option<Duration>,
-    auth: Auth,
+    auth: Option<Arc<dyn AuthProvider>>,
 }
 
 impl Builder {
@@ -30,19 +33,19 @@ impl Builder {
 
     /// Authenticate as a Basic Auth
     /// with username and password.
-    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
-        self.auth = Auth::Basic {
-            username: username.to_owned(),
-            password: password.to_owned(),
-        };
-        self
+    pub fn basic_auth(self, username: &str, password: &str) -> Self {
+        self.auth(BasicAuth::new(username, password))
     }
 
     /// Authenticate with a JWT token.
-    pub fn token(mut self, token: &str) -> Self {
-        self.auth = Auth::Token {
-            token: token.to_owned(),
-        };
+    pub fn token(self, token: &str) -> Self {
+        self.auth(BearerToken::new(token))
+    }
+
+    /// Authenticate with a custom [`AuthProvider`],
+    /// e.g. an [`OAuth2ClientCredentials`](super::OAuth2ClientCredentials) grant.
+    pub fn auth(mut self, auth: impl AuthProvider + 'static) -> Self {
+        self.auth = Some(Arc::new(auth));
         self
     }
 
@@ -68,18 +71,6 @@ impl Builder {
     pub fn build(self) -> Result<BrokerClient> {
         let mut headers = reqwest::header::HeaderMap::new();
 
-        let auth = match self.auth {
-            Auth::None => Auth::None,
-            Auth::Basic { username, password } => Auth::Basic { username, password },
-            Auth::Token { token } => {
-                headers.append(
-                    reqwest::header::AUTHORIZATION,
-                    format!("Bearer {token}").parse().unwrap(),
-                );
-                Auth::None
-            }
-        };
-
         self.extra_headers.into_iter().for_each(|(key, value)| {
             headers.append(key, value.parse().unwrap());
         });
@@ -94,15 +85,20 @@ impl Builder {
         Ok(BrokerClient {
             client,
             base_url: self.base_url.unwrap(),
-            auth,
+            auth: self.auth.unwrap_or_else(|| Arc::new(NoAuth)),
+            index: Default::default(),
         })
     }
+
+    /// Create a blocking [`BrokerClient`](crate::blocking::BrokerClient) instance.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::BrokerClient> {
+        crate::blocking::BrokerClient::new(self.build()?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::auth::Auth;
-
     use super::Builder;
 
     #[test]
@@ -112,7 +108,7 @@ mod tests {
             .unwrap()
             .build()
             .unwrap();
-        assert_eq!(client.auth, Auth::None);
+        assert_eq!(format!("{:?}", client.auth), "NoAuth");
         assert_eq!(client.base_url.as_str(), "http://localhost/");
     }
 
@@ -125,11 +121,8 @@ mod tests {
             .build()
             .unwrap();
         assert_eq!(
-            client.auth,
-            Auth::Basic {
-                username: "John".to_owned(),
-                password: "secret".to_owned()
-            }
+            format!("{:?}", client.auth),
+            r#"BasicAuth { username: "John", .. }"#
         );
         assert_eq!(client.base_url.as_str(), "http://localhost/");
     }
@@ -142,7 +135,7 @@ mod tests {
             .token("foobar123")
             .build()
             .unwrap();
-        assert_eq!(client.auth, Auth::None);
+        assert_eq!(format!("{:?}", client.auth), "BearerToken { .. }");
         assert_eq!(client.base_url.as_str(), "http://localhost/");
     }
 }
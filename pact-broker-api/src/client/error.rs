@@ -1,4 +1,6 @@
+use reqwest::StatusCode;
 use snafu::{Backtrace, ErrorCompat, Snafu};
+use url::Url;
 
 #[derive(Snafu, Debug)]
 #[snafu(visibility(pub))]
@@ -25,6 +27,90 @@ pub enum Error {
         #[snafu(backtrace)]
         backtrace: Option<Backtrace>,
     },
+    #[snafu(display("Authentication Error: {}", message))]
+    Auth { message: String },
+    #[snafu(display("Link Error: {} is templated, it must be expanded first", href))]
+    TemplatedLink { href: String },
+    #[snafu(display("Unsupported: the broker index has no {} relation", relation))]
+    Unsupported { relation: String },
+    #[snafu(display("Runtime Error: {}", source))]
+    Runtime { source: std::io::Error },
+    #[snafu(display("Not Found: {} answered {}: {}", url, status, body))]
+    NotFound {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Unauthorized: {} answered {}: {}", url, status, body))]
+    Unauthorized {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Forbidden: {} answered {}: {}", url, status, body))]
+    Forbidden {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Rate Limited: {} answered {}: {}", url, status, body))]
+    RateLimited {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    #[snafu(display("Server Error: {} answered {}: {}", url, status, body))]
+    ServerError {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    /// Any other `4xx` status, e.g. a `400 Bad Request` or `409 Conflict`
+    #[snafu(display("Client Error: {} answered {}: {}", url, status, body))]
+    ClientError {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+    /// Any other status which is not a success, e.g. a `1xx` or a `3xx` not followed
+    #[snafu(display("Unexpected Status: {} answered {}: {}", url, status, body))]
+    UnexpectedStatus {
+        url: Url,
+        status: StatusCode,
+        body: String,
+    },
+}
+
+impl Error {
+    /// The error of the response `status`, `body` being the error answered by the broker.
+    pub(crate) fn from_status(url: Url, status: StatusCode, body: String) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => Error::NotFound { url, status, body },
+            StatusCode::UNAUTHORIZED => Error::Unauthorized { url, status, body },
+            StatusCode::FORBIDDEN => Error::Forbidden { url, status, body },
+            StatusCode::TOO_MANY_REQUESTS => Error::RateLimited { url, status, body },
+            status if status.is_server_error() => Error::ServerError { url, status, body },
+            status if (400..=499).contains(&status.as_u16()) => {
+                Error::ClientError { url, status, body }
+            }
+            status => Error::UnexpectedStatus { url, status, body },
+        }
+    }
+
+    /// The status answered by the broker, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::NotFound { status, .. }
+            | Error::Unauthorized { status, .. }
+            | Error::Forbidden { status, .. }
+            | Error::RateLimited { status, .. }
+            | Error::ServerError { status, .. }
+            | Error::ClientError { status, .. }
+            | Error::UnexpectedStatus { status, .. } => Some(*status),
+            Error::Http { source, .. } => source.status(),
+            _ => None,
+        }
+    }
 }
 
 impl Error {
@@ -2,7 +2,20 @@ use super::error;
 use super::Result;
 use snafu::ResultExt;
 
+/// Check the status of `response`, turning an error status into the matching [`error::Error`].
+pub(crate) async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let url = response.url().clone();
+    let body = response.text().await.unwrap_or_default();
+    Err(error::Error::from_status(url, status, body))
+}
+
 /// A trait for mapping from a `reqwest::Response` to an another type.
+/// The implementations check the status of the response first, e.g. a `404` is a
+/// [`NotFound`](error::Error::NotFound) error.
 #[async_trait::async_trait]
 pub trait FromResponse: Sized {
     async fn from_response(response: reqwest::Response) -> Result<Self>;
@@ -11,9 +24,22 @@ pub trait FromResponse: Sized {
 #[async_trait::async_trait]
 impl<T: serde::de::DeserializeOwned> FromResponse for T {
     async fn from_response(response: reqwest::Response) -> Result<Self> {
+        let response = check_status(response).await?;
         let text = response.text().await.context(error::HttpSnafu)?;
 
         let de = &mut serde_json::Deserializer::from_str(&text);
         serde_path_to_error::deserialize(de).context(error::JsonSnafu)
     }
 }
+
+/// Body of a response which is not JSON, e.g. the diff between two pact versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text(pub String);
+
+#[async_trait::async_trait]
+impl FromResponse for Text {
+    async fn from_response(response: reqwest::Response) -> Result<Self> {
+        let response = check_status(response).await?;
+        response.text().await.map(Text).context(error::HttpSnafu)
+    }
+}
@@ -2,10 +2,16 @@ mod api;
 mod auth;
 mod broker_client;
 mod builder;
-mod error;
+pub(crate) mod error;
 mod from_response;
 
-pub use broker_client::BrokerClient;
+pub use api::{PacticipantsHandler, PactsHandler, WebhooksHandler};
+pub use auth::{
+    AuthProvider, BasicAuth, BearerToken, CommandToken, NoAuth, OAuth2ClientCredentials,
+};
+pub use broker_client::{BrokerClient, MAX_CONCURRENT_REQUESTS};
 pub use builder::Builder;
+pub use error::Error;
+pub use from_response::{FromResponse, Text};
 
 pub type Result<T, E = error::Error> = std::result::Result<T, E>;
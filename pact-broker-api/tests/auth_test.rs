@@ -0,0 +1,163 @@
+mod mock_error;
+mod mock_index;
+
+use pact_broker_api::client::{self, BrokerClient, OAuth2ClientCredentials};
+use pact_broker_models::pacts::Pacts;
+use serde_json::json;
+use wiremock::{
+    matchers::{body_string_contains, header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .auth(
+            OAuth2ClientCredentials::new(
+                format!("{uri}/oauth2/token").parse().unwrap(),
+                "graph",
+                "s3cr3t",
+            )
+            .with_scope("pactflow"),
+        )
+        .build()
+        .unwrap()
+}
+
+async fn setup_latest(mock_server: &MockServer, token: &str) {
+    mock_index::setup_index(mock_server, "/").await;
+    let latest: Pacts = serde_json::from_str(
+        &include_str!("resources/pacts-latest.json").replace("{{host}}", &mock_server.uri()),
+    )
+    .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/pacts/latest"))
+        .and(header("Authorization", format!("Bearer {token}").as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&latest))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn should_authenticate_with_oauth2_client_credentials() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("client_id=graph"))
+        .and(body_string_contains("scope=pactflow"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "t0k3n",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    setup_latest(&mock_server, "t0k3n").await;
+    mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not authenticated")
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let first = broker_client.pacts().latest().await;
+    let second = broker_client.pacts().latest().await;
+
+    assert!(
+        first.is_ok(),
+        "expected successful result, got error: {first:#?}"
+    );
+    assert!(
+        second.is_ok(),
+        "expected successful result, got error: {second:#?}"
+    );
+}
+
+#[tokio::test]
+async fn should_request_a_single_oauth2_token_for_concurrent_requests() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "access_token": "t0k3n",
+                    "expires_in": 3600,
+                }))
+                .set_delay(std::time::Duration::from_millis(100)),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    setup_latest(&mock_server, "t0k3n").await;
+    mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not authenticated")
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let pacts = broker_client.pacts();
+    let got = futures::future::join_all((0..5).map(|_| pacts.latest())).await;
+
+    assert!(
+        got.iter().all(Result::is_ok),
+        "expected successful results, got: {got:#?}"
+    );
+}
+
+#[tokio::test]
+async fn should_refresh_oauth2_token_when_rejected() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "expired",
+            "expires_in": 3600,
+        })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "fresh",
+            "expires_in": 3600,
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("Authorization", "Bearer expired"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&mock_server)
+        .await;
+    setup_latest(&mock_server, "fresh").await;
+    mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not authenticated")
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client.pacts().latest().await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {got:#?}"
+    );
+}
+
+#[tokio::test]
+async fn should_send_bearer_token() {
+    let mock_server = MockServer::start().await;
+    setup_latest(&mock_server, "foobar123").await;
+    mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not authenticated")
+        .await;
+
+    let broker_client = client::Builder::new()
+        .base_url(mock_server.uri())
+        .unwrap()
+        .token("foobar123")
+        .build()
+        .unwrap();
+    let got = broker_client.pacts().latest().await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {got:#?}"
+    );
+}
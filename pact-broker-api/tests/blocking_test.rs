@@ -0,0 +1,46 @@
+#![cfg(feature = "blocking")]
+
+mod mock_error;
+mod mock_index;
+
+use pact_broker_api::client;
+use pact_broker_models::pacts::Pacts;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[test]
+fn should_return_latest_pact_without_async_runtime() {
+    // the mock server runs on its own runtime, the blocking client drives its own
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mock_server = runtime.block_on(async {
+        let mock_server = MockServer::start().await;
+        mock_index::setup_index(&mock_server, "/").await;
+        Mock::given(method("GET"))
+            .and(path("/pacts/latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                include_str!("resources/pacts-latest.json").replace("{{host}}", &mock_server.uri()),
+            ))
+            .mount(&mock_server)
+            .await;
+        mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not received")
+            .await;
+        mock_server
+    });
+
+    let broker_client = client::Builder::new()
+        .base_url(mock_server.uri())
+        .unwrap()
+        .build_blocking()
+        .unwrap();
+    let got = broker_client.pacts().latest();
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    let Pacts { pacts, .. } = got.unwrap();
+    assert_eq!(pacts.len(), 2);
+}
@@ -0,0 +1,48 @@
+mod mock_index;
+
+use pact_broker_api::client::{self, BrokerClient, Error};
+use rstest::rstest;
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[rstest]
+#[case::not_found(404, |e: &Error| matches!(e, Error::NotFound { .. }))]
+#[case::unauthorized(401, |e: &Error| matches!(e, Error::Unauthorized { .. }))]
+#[case::forbidden(403, |e: &Error| matches!(e, Error::Forbidden { .. }))]
+#[case::rate_limited(429, |e: &Error| matches!(e, Error::RateLimited { .. }))]
+#[case::server_error(503, |e: &Error| matches!(e, Error::ServerError { .. }))]
+#[case::client_error(400, |e: &Error| matches!(e, Error::ClientError { .. }))]
+#[case::unexpected_status(300, |e: &Error| matches!(e, Error::UnexpectedStatus { .. }))]
+#[tokio::test]
+async fn should_return_status_errors(#[case] status: u16, #[case] expected: fn(&Error) -> bool) {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("GET"))
+        .and(path("/pacts/latest"))
+        .respond_with(ResponseTemplate::new(status).set_body_json(json!({
+            "error": "The broker could not serve the pacts"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client.pacts().latest().await;
+
+    let error = got.expect_err("expected an error");
+    assert!(expected(&error), "unexpected error: {:#?}", error);
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(status));
+    assert!(error
+        .to_string()
+        .contains("The broker could not serve the pacts"));
+}
@@ -0,0 +1,100 @@
+mod mock_error;
+mod mock_index;
+
+use pact_broker_api::client::{self, BrokerClient};
+use pact_broker_models::{contract::Contract, index::Capability, pacts::Pacts};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn should_report_broker_capabilities() {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    mock_error::setup_error_handler(&mock_server, "GET on / was not received").await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client.index().await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    let index = got.unwrap();
+    assert!(index.supports(Capability::LatestPacts));
+    assert!(index.supports(Capability::PublishContracts));
+    assert!(!index.supports(Capability::Environments));
+    assert_eq!(index.capabilities().len(), 7);
+
+    let got = broker_client.relation("pb:environments").await;
+    assert!(
+        matches!(got, Err(client::Error::Unsupported { .. })),
+        "expected an unsupported relation error, got: {:#?}",
+        got
+    );
+}
+
+#[tokio::test]
+async fn should_discover_routes_of_broker_under_sub_path() {
+    let mock_server = MockServer::start().await;
+    let host = format!("{}/broker", mock_server.uri());
+    mock_index::setup_index(&mock_server, "/broker").await;
+    Mock::given(method("GET"))
+        .and(path("/broker/pacts/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            include_str!("resources/pacts-latest.json").replace("{{host}}", &host),
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broker/pacts/provider/provider_name/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            include_str!("resources/provider-pacts.json").replace("{{host}}", &host),
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/broker/pacts/provider/provider_name/consumer/consumer_name/version/1.0.0",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            include_str!("resources/pacts-interactions.json").replace("{{host}}", &host),
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(&mock_server, "GET under /broker was not received").await;
+
+    let broker_client = setup_client(&host);
+    let latest = broker_client.pacts().latest().await;
+    let contract = broker_client
+        .pacts()
+        .contract("provider_name", "consumer_name", None)
+        .await;
+
+    assert!(
+        latest.is_ok(),
+        "expected successful result, got error: {:#?}",
+        latest
+    );
+    let Pacts { pacts, .. } = latest.unwrap();
+    assert_eq!(pacts.len(), 2);
+    assert!(
+        contract.is_ok(),
+        "expected successful result, got error: {:#?}",
+        contract
+    );
+    let Contract { consumer, .. } = contract.unwrap();
+    assert_eq!(consumer.name, "consumer_name");
+}
@@ -0,0 +1,18 @@
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+// Sets up the index of a broker mounted under `base_path`, e.g. `/` or `/broker`,
+// linking to the resources served by the mock server.
+pub async fn setup_index(mock_server: &MockServer, base_path: &str) {
+    let host = format!("{}{}", mock_server.uri(), base_path.trim_end_matches('/'));
+    Mock::given(method("GET"))
+        .and(path(base_path))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("resources/index.json").replace("{{host}}", &host)),
+        )
+        .mount(mock_server)
+        .await;
+}
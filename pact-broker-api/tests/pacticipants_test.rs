@@ -0,0 +1,54 @@
+mod mock_error;
+mod mock_index;
+
+use pact_broker_api::client::{self, BrokerClient};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn should_list_pacticipants() {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("GET"))
+        .and(path("/pacticipants"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "_embedded": {
+                "pacticipants": [
+                    { "name": "consumer_name", "displayName": "Consumer Name", "createdAt": "2023-01-10T10:00:00+00:00" },
+                    { "name": "provider_name" }
+                ]
+            },
+            "_links": { "self": { "href": format!("{}/pacticipants", mock_server.uri()) } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(&mock_server, "GET on /pacticipants was not received").await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let pacticipants = broker_client.pacticipants().list().await;
+
+    assert!(
+        pacticipants.is_ok(),
+        "expected successful result, got error: {:#?}",
+        pacticipants
+    );
+    let names: Vec<String> = pacticipants
+        .unwrap()
+        .pacticipants()
+        .iter()
+        .map(|pacticipant| pacticipant.name.clone())
+        .collect();
+    assert_eq!(names, vec!["consumer_name", "provider_name"]);
+}
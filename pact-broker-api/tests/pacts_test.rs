@@ -1,7 +1,12 @@
 mod mock_error;
+mod mock_index;
 
-use pact_broker_api::client::{self, BrokerClient};
-use pact_broker_models::{contract::Contract, pacts::Pacts};
+use pact_broker_api::client::{self, BrokerClient, Error};
+use pact_broker_models::{
+    contract::{Contract, Interaction},
+    pacts::Pacts,
+};
+use serde_json::json;
 use wiremock::{
     matchers::{method, path},
     Mock, MockServer, ResponseTemplate,
@@ -15,6 +20,17 @@ fn setup_client(uri: &str) -> BrokerClient {
         .unwrap()
 }
 
+// Sets up the latest pacts of `provider_name`, linking to the contract of `consumer_name`.
+async fn setup_provider_pacts(mock_server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/pacts/provider/provider_name/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            include_str!("resources/provider-pacts.json").replace("{{host}}", &mock_server.uri()),
+        ))
+        .mount(mock_server)
+        .await;
+}
+
 #[tokio::test]
 async fn should_return_latest_pact() {
     let mock_server = MockServer::start().await;
@@ -28,6 +44,7 @@ async fn should_return_latest_pact() {
         .respond_with(template)
         .mount(&mock_server)
         .await;
+    mock_index::setup_index(&mock_server, "/").await;
     mock_error::setup_error_handler(&mock_server, "GET on /pacts/latest was not received").await;
     // let mock_server = setup_api(template).await;
 
@@ -62,14 +79,16 @@ async fn should_return_latest_interaction() {
     let template = ResponseTemplate::new(200).set_body_json(&latest);
     Mock::given(method("GET"))
         .and(path(
-            "/pacts/provider/provider_name/consumer/consumer_name/latest",
+            "/pacts/provider/provider_name/consumer/consumer_name/version/1.0.0",
         ))
         .respond_with(template)
         .mount(&mock_server)
         .await;
+    mock_index::setup_index(&mock_server, "/").await;
+    setup_provider_pacts(&mock_server).await;
     mock_error::setup_error_handler(
         &mock_server,
-        "GET on /pacts/provider/provider_name/consumer/consumer_name/latest was not received",
+        "GET on /pacts/provider/provider_name/consumer/consumer_name/version/1.0.0 was not received",
     )
     .await;
 
@@ -96,7 +115,334 @@ async fn should_return_latest_interaction() {
     assert_eq!(provider.name, "provider_name");
     let interactions = interactions.unwrap();
     assert_eq!(interactions.len(), 1);
-    let interation = interactions.first().unwrap();
+    let interation = interactions.first().unwrap().as_http().unwrap();
     assert_eq!(interation.request.method, "GET");
     assert_eq!(interation.request.path, "/api/profiles");
+    let response = &interation.response;
+    assert_eq!(
+        response.headers.as_ref().unwrap()["Content-Type"].values(),
+        vec!["application/json"]
+    );
+    assert_eq!(
+        response.matched_fields(),
+        vec!["$.content.email", "$.content.uuid"]
+    );
+}
+
+#[tokio::test]
+async fn should_return_v4_interactions() {
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200)
+        .set_body_string(include_str!("resources/pacts-v4-interactions.json"));
+    Mock::given(method("GET"))
+        .and(path(
+            "/pacts/provider/provider_name/consumer/consumer_name/version/1.0.0",
+        ))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+    mock_index::setup_index(&mock_server, "/").await;
+    setup_provider_pacts(&mock_server).await;
+    mock_error::setup_error_handler(
+        &mock_server,
+        "GET on /pacts/provider/provider_name/consumer/consumer_name/version/1.0.0 was not received",
+    )
+    .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client
+        .pacts()
+        .contract("provider_name", "consumer_name", None)
+        .await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+
+    let Contract {
+        interactions,
+        metadata,
+        ..
+    } = got.unwrap();
+
+    let metadata = metadata.unwrap();
+    assert_eq!(metadata.pact_specification.version, "4.0");
+    assert_eq!(metadata.plugins.unwrap()[0].name, "protobuf");
+
+    let interactions = interactions.unwrap();
+    assert_eq!(interactions.len(), 3);
+
+    let http = interactions[0].as_http().unwrap();
+    assert!(interactions[0].is_pending());
+    assert_eq!(http.request.path, "/api/profiles");
+    let state = &http.provider_states.as_ref().unwrap()[0];
+    assert_eq!(
+        state.params.as_ref().unwrap()["uuid"],
+        "5fa95f64-7517-4653-f3bc-2c963f66afa6"
+    );
+
+    match &interactions[1] {
+        Interaction::AsynchronousMessage(message) => {
+            assert_eq!(
+                message.metadata.as_ref().unwrap()["topic"],
+                "profile.created"
+            )
+        }
+        other => panic!("expected an asynchronous message, got {:#?}", other),
+    }
+
+    match &interactions[2] {
+        Interaction::SynchronousMessage(message) => {
+            assert_eq!(message.response.len(), 1);
+            assert!(message.plugin_configuration.is_some());
+        }
+        other => panic!("expected a synchronous message, got {:#?}", other),
+    }
+}
+
+#[tokio::test]
+async fn should_return_the_contract_of_a_consumer_version() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/pacts/provider/provider_name/consumer/consumer_name/version/0.9.0",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(
+                include_str!("resources/pacts-interactions.json")
+                    .replace("{{host}}", &mock_server.uri()),
+            ),
+        )
+        .mount(&mock_server)
+        .await;
+    mock_index::setup_index(&mock_server, "/").await;
+    setup_provider_pacts(&mock_server).await;
+    mock_error::setup_error_handler(
+        &mock_server,
+        "GET on /pacts/provider/provider_name/consumer/consumer_name/version/0.9.0 was not received",
+    )
+    .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client
+        .pacts()
+        .contract("provider_name", "consumer_name", Some("0.9.0"))
+        .await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    let missing = broker_client
+        .pacts()
+        .contract("provider_name", "unknown_consumer", None)
+        .await;
+    assert!(
+        matches!(
+            missing,
+            Err(Error::NotFound { .. }) | Err(Error::ServerError { .. })
+        ),
+        "expected a not found error, got: {:#?}",
+        missing
+    );
+}
+
+#[tokio::test]
+async fn should_tell_the_path_of_a_malformed_interaction() {
+    let mock_server = MockServer::start().await;
+    let template = ResponseTemplate::new(200).set_body_json(json!({
+        "consumer": { "name": "consumer_name" },
+        "provider": { "name": "provider_name" },
+        "interactions": [
+            {
+                "description": "a request",
+                "request": { "method": "get", "path": "/" },
+                "response": { "status": 200 }
+            },
+            {
+                "description": "a malformed request",
+                "request": { "method": 5, "path": "/" },
+                "response": { "status": 200 }
+            }
+        ]
+    }));
+    Mock::given(method("GET"))
+        .and(path(
+            "/pacts/provider/provider_name/consumer/consumer_name/version/1.0.0",
+        ))
+        .respond_with(template)
+        .mount(&mock_server)
+        .await;
+    mock_index::setup_index(&mock_server, "/").await;
+    setup_provider_pacts(&mock_server).await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let got = broker_client
+        .pacts()
+        .contract("provider_name", "consumer_name", None)
+        .await;
+
+    match got {
+        Err(Error::Json { source, .. }) => {
+            assert_eq!(source.path().to_string(), "interactions[1].request.method")
+        }
+        got => panic!("expected a JSON error, got: {got:#?}"),
+    }
+}
+
+#[tokio::test]
+async fn should_follow_contract_links() {
+    let mock_server = MockServer::start().await;
+    let body =
+        include_str!("resources/pacts-interactions.json").replace("{{host}}", &mock_server.uri());
+    Mock::given(method("GET"))
+        .and(path(
+            "/pacts/provider/provider_name/consumer/consumer_name/latest/prod",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&body))
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(
+        &mock_server,
+        "GET on /pacts/provider/provider_name/consumer/consumer_name/latest/prod was not received",
+    )
+    .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let contract: Contract = serde_json::from_str(&body).unwrap();
+    let links = contract.links.unwrap();
+    let latest_tagged = links.get("pb:latest-tagged-pact-version").unwrap();
+
+    let got = broker_client.follow::<Contract>(latest_tagged).await;
+    assert!(
+        matches!(got, Err(client::Error::TemplatedLink { .. })),
+        "expected a templated link error, got: {:#?}",
+        got
+    );
+
+    let got = broker_client
+        .follow::<Contract>(&latest_tagged.expand(&[("tag", "prod")]))
+        .await;
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    assert_eq!(got.unwrap().consumer.name, "consumer_name");
+    assert_eq!(
+        links.expand_rel("pb:consumer"),
+        format!("{}/doc/consumer?context=pact", mock_server.uri())
+    );
+}
+
+#[tokio::test]
+async fn should_return_pact_history() {
+    let mock_server = MockServer::start().await;
+    let host = mock_server.uri();
+    let body = include_str!("resources/pacts-interactions.json").replace("{{host}}", &host);
+    let base = "/pacts/provider/provider_name/consumer/consumer_name";
+    Mock::given(method("GET"))
+        .and(path(format!("{base}/versions")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "_links": {
+                "pb:pact-versions": [
+                    { "href": format!("{host}{base}/version/v0.15.5"), "title": "Pact version", "name": "Version v0.15.5" },
+                    { "href": format!("{host}{base}/version/v0.15.4"), "title": "Pact version", "name": "Version v0.15.4" }
+                ]
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{base}/version/v0.15.5/previous-distinct")))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&body))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "{base}/version/v0.15.5/diff/previous-distinct"
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "{\n  \"interactions\": [\n+    {\n+      \"description\": \"a request for profiles\"",
+        ))
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(&mock_server, "GET on the pact history was not received").await;
+
+    let broker_client = setup_client(&host);
+    let contract: Contract = serde_json::from_str(&body).unwrap();
+    let versions = broker_client.pacts().versions(&contract).await;
+    let previous = broker_client.pacts().previous_distinct(&contract).await;
+    let diff = broker_client
+        .pacts()
+        .diff_previous_distinct(&contract)
+        .await;
+
+    assert!(
+        versions.is_ok(),
+        "expected successful result, got error: {:#?}",
+        versions
+    );
+    assert_eq!(versions.unwrap().versions().len(), 2);
+    assert!(
+        previous.is_ok(),
+        "expected successful result, got error: {:#?}",
+        previous
+    );
+    assert!(
+        diff.is_ok(),
+        "expected successful result, got error: {:#?}",
+        diff
+    );
+    assert!(diff.unwrap().contains("+      \"description\""));
+}
+
+#[tokio::test]
+async fn should_return_the_latest_verification() {
+    let mock_server = MockServer::start().await;
+    let host = mock_server.uri();
+    let body = include_str!("resources/pacts-interactions.json").replace("{{host}}", &host);
+    let verifications = "/pacts/provider/provider_name/consumer/consumer_name/pact-version/61b543ff0d0fd90e7e2e48813ac5d7aab0497921/verification-results/latest";
+    Mock::given(method("GET"))
+        .and(path(verifications))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "providerName": "provider_name",
+            "providerApplicationVersion": "1.0.0",
+            "success": true,
+            "verificationDate": "2023-01-10T10:00:00+00:00"
+        })))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(verifications))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(
+        &mock_server,
+        "GET on the latest verification was not received",
+    )
+    .await;
+
+    let broker_client = setup_client(&host);
+    let contract: Contract = serde_json::from_str(&body).unwrap();
+    let verified = broker_client.pacts().latest_verification(&contract).await;
+    let never_verified = broker_client.pacts().latest_verification(&contract).await;
+
+    assert!(
+        verified.is_ok(),
+        "expected successful result, got error: {:#?}",
+        verified
+    );
+    let verification = verified.unwrap().expect("a verification");
+    assert!(verification.success);
+    assert_eq!(
+        verification.verification_date.as_deref(),
+        Some("2023-01-10T10:00:00+00:00")
+    );
+    assert!(matches!(never_verified, Ok(None)));
 }
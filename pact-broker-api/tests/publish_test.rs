@@ -0,0 +1,194 @@
+mod mock_error;
+mod mock_index;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pact_broker_api::client::{self, BrokerClient};
+use pact_broker_models::{
+    contract::Contract,
+    pacticipants::NewVersion,
+    publish::{ContractToPublish, PublishContracts, VerificationResult},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+const PACT: &str = include_str!("resources/pacts-interactions.json");
+
+fn contract(host: &str) -> Contract {
+    serde_json::from_str(&PACT.replace("{{host}}", host)).unwrap()
+}
+
+#[tokio::test]
+async fn should_publish_contracts() {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("POST"))
+        .and(path("/contracts/publish"))
+        .and(body_partial_json(json!({
+            "pacticipantName": "consumer_name",
+            "pacticipantVersionNumber": "v0.15.5",
+            "branch": "main",
+            "tags": ["prod"],
+            "contracts": [{
+                "consumerName": "consumer_name",
+                "providerName": "provider_name",
+                "specification": "pact",
+                "contentType": "application/json"
+            }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notices": [{ "type": "success", "text": "Created consumer_name version v0.15.5" }],
+            "_links": {
+                "pb:pacticipant-version": { "href": format!("{}/pacticipants/consumer_name/versions/v0.15.5", mock_server.uri()) }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_error::setup_error_handler(&mock_server, "POST on /contracts/publish was not received")
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let contracts = PublishContracts::new("consumer_name", "v0.15.5")
+        .with_branch("main")
+        .with_tag("prod")
+        .with_contract(ContractToPublish::pact(PACT.as_bytes()).unwrap());
+    let got = broker_client.pacts().publish(&contracts).await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    let results = got.unwrap();
+    assert_eq!(results.notices[0].kind, "success");
+    assert!(results.links.get("pb:pacticipant-version").is_some());
+}
+
+#[tokio::test]
+async fn should_publish_the_pact_file_unchanged() {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("POST"))
+        .and(path("/contracts/publish"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "notices": [] })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let contracts = PublishContracts::new("consumer_name", "v0.15.5")
+        .with_contract(ContractToPublish::pact(PACT.as_bytes()).unwrap());
+    broker_client.pacts().publish(&contracts).await.unwrap();
+
+    let requests = mock_server.received_requests().await.unwrap();
+    let published: PublishContracts = requests
+        .iter()
+        .find(|request| request.url.path() == "/contracts/publish")
+        .map(|request| serde_json::from_slice(&request.body).unwrap())
+        .unwrap();
+    let content = STANDARD.decode(&published.contracts[0].content).unwrap();
+    assert_eq!(content, PACT.as_bytes());
+}
+
+#[tokio::test]
+async fn should_publish_verification_results() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/pacts/provider/provider_name/consumer/consumer_name/pact-version/61b543ff0d0fd90e7e2e48813ac5d7aab0497921/metadata/Y3Y9NDc5Nw/verification-results"))
+        .and(body_partial_json(json!({
+            "success": true,
+            "providerApplicationVersion": "1.2.3"
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "providerName": "provider_name",
+            "providerApplicationVersion": "1.2.3",
+            "success": true,
+            "verificationDate": "2023-01-10T12:00:00+00:00"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let result = VerificationResult {
+        success: true,
+        provider_application_version: "1.2.3".to_owned(),
+        build_url: None,
+        test_results: None,
+        verified_by: None,
+    };
+    let got = broker_client
+        .pacts()
+        .publish_verification(&contract(&mock_server.uri()), &result)
+        .await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    assert!(got.unwrap().success);
+}
+
+#[tokio::test]
+async fn should_create_and_tag_versions() {
+    let mock_server = MockServer::start().await;
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("PUT"))
+        .and(path("/pacticipants/consumer_name/versions/1.0.0"))
+        .and(body_partial_json(json!({ "branch": "feat/a" })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "number": "1.0.0",
+            "createdAt": "2023-01-10T12:00:00+00:00"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/pacticipants/consumer_name/versions/1.0.0/tags/prod"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "name": "prod" })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let broker_client = setup_client(&mock_server.uri());
+    let version = broker_client
+        .pacticipants()
+        .create_version(
+            "consumer_name",
+            "1.0.0",
+            &NewVersion {
+                branch: Some("feat/a".to_owned()),
+                build_url: None,
+            },
+        )
+        .await;
+    let tag = broker_client
+        .pacticipants()
+        .tag_version("consumer_name", "1.0.0", "prod")
+        .await;
+
+    assert!(
+        version.is_ok(),
+        "expected successful result, got error: {:#?}",
+        version
+    );
+    assert_eq!(version.unwrap().number, "1.0.0");
+    assert!(
+        tag.is_ok(),
+        "expected successful result, got error: {:#?}",
+        tag
+    );
+    assert_eq!(tag.unwrap().name, "prod");
+}
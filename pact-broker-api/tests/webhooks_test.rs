@@ -0,0 +1,198 @@
+mod mock_error;
+mod mock_index;
+
+use pact_broker_api::client::{self, BrokerClient};
+use pact_broker_models::{
+    contract::Contract,
+    webhooks::{Event, TriggeredStatus, Webhook, WebhookRequest},
+};
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn setup_client(uri: &str) -> BrokerClient {
+    client::Builder::new()
+        .base_url(uri)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+fn contract(host: &str) -> Contract {
+    serde_json::from_str(
+        &include_str!("resources/pacts-interactions.json").replace("{{host}}", host),
+    )
+    .unwrap()
+}
+
+fn webhook(host: &str, uuid: &str, description: &str) -> Value {
+    json!({
+        "uuid": uuid,
+        "description": description,
+        "consumer": { "name": "consumer_name" },
+        "provider": { "name": "provider_name" },
+        "enabled": true,
+        "request": { "method": "POST", "url": "https://ci/build" },
+        "events": [{ "name": "contract_content_changed" }],
+        "_links": {
+            "self": { "href": format!("{host}/webhooks/{uuid}") },
+            "pb:execute": { "href": format!("{host}/webhooks/{uuid}/execute") }
+        }
+    })
+}
+
+async fn mount_get(mock_server: &MockServer, route: &str, body: Value) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn should_list_webhooks() {
+    let mock_server = MockServer::start().await;
+    let host = mock_server.uri();
+    mock_index::setup_index(&mock_server, "/").await;
+    let links = json!({
+        "_links": {
+            "pb:webhooks": [
+                { "href": format!("{host}/webhooks/w1"), "title": "Build" },
+                { "href": format!("{host}/webhooks/w2"), "title": "Notify" }
+            ]
+        }
+    });
+    mount_get(&mock_server, "/webhooks", links.clone()).await;
+    mount_get(
+        &mock_server,
+        "/webhooks/provider/provider_name/consumer/consumer_name",
+        links,
+    )
+    .await;
+    mount_get(&mock_server, "/webhooks/w1", webhook(&host, "w1", "Build")).await;
+    mount_get(&mock_server, "/webhooks/w2", webhook(&host, "w2", "Notify")).await;
+    mock_error::setup_error_handler(&mock_server, "GET on /webhooks was not received").await;
+
+    let broker_client = setup_client(&host);
+    let all = broker_client.webhooks().list().await;
+    let for_pact = broker_client.webhooks().for_pact(&contract(&host)).await;
+
+    assert!(
+        all.is_ok(),
+        "expected successful result, got error: {:#?}",
+        all
+    );
+    let names: Vec<String> = all.unwrap().iter().map(Webhook::name).collect();
+    assert_eq!(names, vec!["Build", "Notify"]);
+    assert!(
+        for_pact.is_ok(),
+        "expected successful result, got error: {:#?}",
+        for_pact
+    );
+    assert_eq!(for_pact.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn should_create_and_execute_a_webhook() {
+    let mock_server = MockServer::start().await;
+    let host = mock_server.uri();
+    mock_index::setup_index(&mock_server, "/").await;
+    Mock::given(method("POST"))
+        .and(path("/webhooks"))
+        .and(body_partial_json(json!({
+            "request": { "method": "POST", "url": "https://ci/build" },
+            "events": [{ "name": "contract_content_changed" }]
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(webhook(&host, "w1", "Build")))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/webhooks/w1/execute"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": false,
+            "logs": "HTTP/1.1 500 Internal Server Error"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let broker_client = setup_client(&host);
+    let created = broker_client
+        .webhooks()
+        .create(&Webhook {
+            uuid: None,
+            description: Some("Build".to_owned()),
+            consumer: None,
+            provider: None,
+            enabled: None,
+            request: WebhookRequest {
+                method: "POST".to_owned(),
+                url: "https://ci/build".to_owned(),
+                headers: None,
+                body: None,
+            },
+            events: vec![Event {
+                name: "contract_content_changed".to_owned(),
+            }],
+            created_at: None,
+            links: Default::default(),
+        })
+        .await;
+
+    assert!(
+        created.is_ok(),
+        "expected successful result, got error: {:#?}",
+        created
+    );
+    let created = created.unwrap();
+    assert_eq!(created.uuid.as_deref(), Some("w1"));
+    let execution = broker_client.webhooks().execute(&created).await;
+    assert!(
+        execution.is_ok(),
+        "expected successful result, got error: {:#?}",
+        execution
+    );
+    assert!(!execution.unwrap().success);
+}
+
+#[tokio::test]
+async fn should_return_triggered_webhooks() {
+    let mock_server = MockServer::start().await;
+    let host = mock_server.uri();
+    mount_get(
+        &mock_server,
+        "/pacts/provider/provider_name/consumer/consumer_name/version/v0.15.5/triggered-webhooks",
+        json!({
+            "_embedded": {
+                "triggeredWebhooks": [{
+                    "name": "Build",
+                    "status": "failure",
+                    "eventName": "contract_content_changed",
+                    "attemptsMade": 6,
+                    "attemptsRemaining": 0,
+                    "triggeredAt": "2023-01-10T12:00:00+00:00",
+                    "_links": { "pb:webhook": { "href": format!("{host}/webhooks/w1") } }
+                }]
+            }
+        }),
+    )
+    .await;
+
+    let broker_client = setup_client(&host);
+    let got = broker_client.webhooks().triggered(&contract(&host)).await;
+
+    assert!(
+        got.is_ok(),
+        "expected successful result, got error: {:#?}",
+        got
+    );
+    let triggered = got.unwrap();
+    assert_eq!(triggered[0].status, TriggeredStatus::Failure);
+    assert_eq!(
+        triggered[0].webhook().unwrap().href,
+        format!("{host}/webhooks/w1")
+    );
+}
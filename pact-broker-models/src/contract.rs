@@ -1,15 +1,32 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+pub use crate::hal::{Curie, Link, Links};
+use crate::matching::{Generators, MatchingRules};
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Contract {
     pub consumer: Pacticant,
     pub interactions: Option<Vec<Interaction>>,
+    /// Messages of a message pact (v3)
+    pub messages: Option<Vec<Message>>,
     pub metadata: Option<Metadata>,
     pub provider: Pacticant,
+    /// Set by the broker, absent from pact files written by the consumer tests
     #[serde(rename = "createdAt")]
-    pub created_at: String,
+    pub created_at: Option<String>,
+    /// Set by the broker, absent from pact files written by the consumer tests
     #[serde(rename = "_links")]
-    pub links: Links,
+    pub links: Option<Links>,
+}
+
+impl Contract {
+    /// The first link of the relation `rel`, e.g. `pb:consumer-version`,
+    /// `None` for a pact file which was not fetched from a broker.
+    pub fn link(&self, rel: &str) -> Option<&Link> {
+        self.links.as_ref().and_then(|links| links.get(rel))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -17,20 +34,348 @@ pub struct Pacticant {
     pub name: String,
 }
 
+/// An interaction of the contract.
+///
+/// Pact specification v4 tells the kind of interaction with its `type`,
+/// interactions of previous versions are all HTTP interactions.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum Interaction {
+    #[serde(rename = "Synchronous/HTTP")]
+    Http(Box<HttpInteraction>),
+    #[serde(rename = "Asynchronous/Messages")]
+    AsynchronousMessage(Box<Message>),
+    #[serde(rename = "Synchronous/Messages")]
+    SynchronousMessage(Box<SynchronousMessage>),
+}
+
+const SYNCHRONOUS_HTTP: &str = "Synchronous/HTTP";
+const ASYNCHRONOUS_MESSAGES: &str = "Asynchronous/Messages";
+const SYNCHRONOUS_MESSAGES: &str = "Synchronous/Messages";
+
+impl<'de> Deserialize<'de> for Interaction {
+    /// The fields of every kind of interaction are deserialized in place,
+    /// so that an error tells its path inside the interaction, e.g. `request.method`,
+    /// then the interaction is built according to its `type`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let raw = RawInteraction::deserialize(deserializer)?;
+        match raw.kind.as_deref().unwrap_or(SYNCHRONOUS_HTTP) {
+            SYNCHRONOUS_HTTP => {
+                let request = match raw.request {
+                    Some(RawRequest {
+                        method: Some(method),
+                        path: Some(path),
+                        query,
+                        headers,
+                        body,
+                        matching_rules,
+                        generators,
+                        ..
+                    }) => Request {
+                        method,
+                        path,
+                        query,
+                        headers,
+                        body,
+                        matching_rules,
+                        generators,
+                        route: None,
+                    },
+                    Some(RawRequest { method: None, .. }) => {
+                        return Err(D::Error::missing_field("request.method"))
+                    }
+                    Some(RawRequest { path: None, .. }) => {
+                        return Err(D::Error::missing_field("request.path"))
+                    }
+                    None => return Err(D::Error::missing_field("request")),
+                };
+                let response = match raw.response {
+                    Some(RawResponse::Http(response)) => *response,
+                    Some(RawResponse::Messages(_)) => {
+                        return Err(D::Error::invalid_type(
+                            serde::de::Unexpected::Seq,
+                            &"the response of an HTTP interaction",
+                        ))
+                    }
+                    None => return Err(D::Error::missing_field("response")),
+                };
+                Ok(Interaction::Http(Box::new(HttpInteraction {
+                    id: raw.id,
+                    key: raw.key,
+                    description: raw.description,
+                    provider_state: raw.provider_state,
+                    provider_states: raw.provider_states,
+                    request,
+                    response,
+                    pending: raw.pending,
+                    comments: raw.comments,
+                    plugin_configuration: raw.plugin_configuration,
+                })))
+            }
+            ASYNCHRONOUS_MESSAGES => Ok(Interaction::AsynchronousMessage(Box::new(Message {
+                id: raw.id,
+                key: raw.key,
+                description: raw.description,
+                provider_states: raw.provider_states,
+                contents: raw.contents,
+                metadata: raw.metadata,
+                matching_rules: raw.matching_rules,
+                generators: raw.generators,
+                pending: raw.pending,
+                comments: raw.comments,
+                plugin_configuration: raw.plugin_configuration,
+            }))),
+            SYNCHRONOUS_MESSAGES => {
+                let request = match raw.request {
+                    Some(request) => MessageContents {
+                        contents: request.contents,
+                        metadata: request.metadata,
+                        matching_rules: request.matching_rules,
+                        generators: request.generators,
+                    },
+                    None => return Err(D::Error::missing_field("request")),
+                };
+                let response = match raw.response {
+                    Some(RawResponse::Messages(response)) => response,
+                    Some(RawResponse::Http(_)) => {
+                        return Err(D::Error::invalid_type(
+                            serde::de::Unexpected::Map,
+                            &"the response messages of a synchronous message",
+                        ))
+                    }
+                    None => vec![],
+                };
+                Ok(Interaction::SynchronousMessage(Box::new(
+                    SynchronousMessage {
+                        key: raw.key,
+                        description: raw.description,
+                        provider_states: raw.provider_states,
+                        request,
+                        response,
+                        pending: raw.pending,
+                        comments: raw.comments,
+                        plugin_configuration: raw.plugin_configuration,
+                    },
+                )))
+            }
+            kind => Err(D::Error::unknown_variant(
+                kind,
+                &[
+                    SYNCHRONOUS_HTTP,
+                    ASYNCHRONOUS_MESSAGES,
+                    SYNCHRONOUS_MESSAGES,
+                ],
+            )),
+        }
+    }
+}
+
+/// The fields of every kind of interaction.
+#[derive(Deserialize)]
+struct RawInteraction {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(rename = "_id")]
+    id: Option<String>,
+    key: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "providerState")]
+    provider_state: Option<String>,
+    #[serde(rename = "providerStates")]
+    provider_states: Option<Vec<State>>,
+    request: Option<RawRequest>,
+    response: Option<RawResponse>,
+    contents: Option<serde_json::Value>,
+    metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "matchingRules")]
+    matching_rules: Option<MatchingRules>,
+    generators: Option<Generators>,
+    pending: Option<bool>,
+    comments: Option<Comments>,
+    #[serde(rename = "pluginConfiguration")]
+    plugin_configuration: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// The fields of an HTTP request and of a request message.
+#[derive(Deserialize)]
+struct RawRequest {
+    method: Option<String>,
+    path: Option<String>,
+    query: Option<serde_json::Value>,
+    headers: Option<Headers>,
+    body: Option<serde_json::Value>,
+    contents: Option<serde_json::Value>,
+    metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "matchingRules")]
+    matching_rules: Option<MatchingRules>,
+    generators: Option<Generators>,
+}
+
+/// An HTTP response, or the response messages of a synchronous message.
+enum RawResponse {
+    Http(Box<Response>),
+    Messages(Vec<MessageContents>),
+}
+
+impl<'de> Deserialize<'de> for RawResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+
+        struct RawResponseVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for RawResponseVisitor {
+            type Value = RawResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("an HTTP response or a list of response messages")
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                Response::deserialize(MapAccessDeserializer::new(map))
+                    .map(|response| RawResponse::Http(Box::new(response)))
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                Vec::deserialize(SeqAccessDeserializer::new(seq)).map(RawResponse::Messages)
+            }
+        }
+
+        deserializer.deserialize_any(RawResponseVisitor)
+    }
+}
+
+impl Interaction {
+    /// The HTTP interaction, `None` for messages.
+    pub fn as_http(&self) -> Option<&HttpInteraction> {
+        match self {
+            Interaction::Http(interaction) => Some(interaction.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// The HTTP interaction to modify, `None` for messages.
+    pub fn as_http_mut(&mut self) -> Option<&mut HttpInteraction> {
+        match self {
+            Interaction::Http(interaction) => Some(interaction.as_mut()),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            Interaction::Http(interaction) => interaction.description.as_deref(),
+            Interaction::AsynchronousMessage(message) => message.description.as_deref(),
+            Interaction::SynchronousMessage(message) => message.description.as_deref(),
+        }
+    }
+
+    /// Whether the interaction is pending, i.e. its verification failure does not break the build (v4).
+    pub fn is_pending(&self) -> bool {
+        match self {
+            Interaction::Http(interaction) => interaction.pending,
+            Interaction::AsynchronousMessage(message) => message.pending,
+            Interaction::SynchronousMessage(message) => message.pending,
+        }
+        .unwrap_or(false)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Interaction {
+pub struct HttpInteraction {
+    /// Set by the broker
     #[serde(rename = "_id")]
-    pub id: String,
+    pub id: Option<String>,
+    /// Unique key of the interaction (v4)
+    pub key: Option<String>,
     pub description: Option<String>,
+    /// Provider state of pact specification v2
+    #[serde(rename = "providerState")]
+    pub provider_state: Option<String>,
     #[serde(rename = "providerStates")]
     pub provider_states: Option<Vec<State>>,
     pub request: Request,
     pub response: Response,
+    pub pending: Option<bool>,
+    pub comments: Option<Comments>,
+    #[serde(rename = "pluginConfiguration")]
+    pub plugin_configuration: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// A message sent by the provider, and consumed by the consumer.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Message {
+    /// Set by the broker
+    #[serde(rename = "_id")]
+    pub id: Option<String>,
+    /// Unique key of the interaction (v4)
+    pub key: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "providerStates")]
+    pub provider_states: Option<Vec<State>>,
+    pub contents: Option<serde_json::Value>,
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "matchingRules")]
+    pub matching_rules: Option<MatchingRules>,
+    pub generators: Option<Generators>,
+    pub pending: Option<bool>,
+    pub comments: Option<Comments>,
+    #[serde(rename = "pluginConfiguration")]
+    pub plugin_configuration: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// A request message answered by one or several response messages (v4).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SynchronousMessage {
+    /// Unique key of the interaction
+    pub key: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "providerStates")]
+    pub provider_states: Option<Vec<State>>,
+    pub request: MessageContents,
+    #[serde(default)]
+    pub response: Vec<MessageContents>,
+    pub pending: Option<bool>,
+    pub comments: Option<Comments>,
+    #[serde(rename = "pluginConfiguration")]
+    pub plugin_configuration: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MessageContents {
+    pub contents: Option<serde_json::Value>,
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "matchingRules")]
+    pub matching_rules: Option<MatchingRules>,
+    pub generators: Option<Generators>,
+}
+
+/// Comments attached to an interaction (v4).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Comments {
+    pub text: Option<Vec<String>>,
+    pub testname: Option<String>,
+    pub references: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct State {
     pub name: String,
+    /// Parameters of the provider state (v3)
+    pub params: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -38,85 +383,84 @@ pub struct Request {
     pub method: String,
     pub path: String,
     pub query: Option<serde_json::Value>,
+    pub headers: Option<Headers>,
+    /// Body of the request, an object with `content`, `contentType` and `encoded` in v4
+    pub body: Option<serde_json::Value>,
+    #[serde(rename = "matchingRules")]
+    pub matching_rules: Option<MatchingRules>,
+    pub generators: Option<Generators>,
+    /// Template of the path, e.g. `/orders/{id}` for `/orders/123`, when it was computed
+    #[serde(skip)]
+    pub route: Option<String>,
+}
+
+impl Request {
+    /// The template of the path if any, the path otherwise.
+    pub fn route(&self) -> &str {
+        self.route.as_deref().unwrap_or(&self.path)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Response {
     pub status: i16,
-    pub headers: Option<serde_json::Value>,
+    pub headers: Option<Headers>,
+    /// Body of the response, an object with `content`, `contentType` and `encoded` in v4
     pub body: Option<serde_json::Value>,
+    #[serde(rename = "matchingRules")]
+    pub matching_rules: Option<MatchingRules>,
+    pub generators: Option<Generators>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Links {
-    #[serde(rename = "self")]
-    links_self: Link,
-    #[serde(rename = "pb:consumer")]
-    pb_consumer: Link,
-    #[serde(rename = "pb:consumer-version")]
-    pb_consumer_version: Link,
-    #[serde(rename = "pb:provider")]
-    pb_provider: Link,
-    #[serde(rename = "pb:pact-version")]
-    pb_pact_version: Link,
-    #[serde(rename = "pb:latest-pact-version")]
-    pb_latest_pact_version: Link,
-    #[serde(rename = "pb:all-pact-versions")]
-    pb_all_pact_versions: Link,
-    #[serde(rename = "pb:latest-untagged-pact-version")]
-    pb_latest_untagged_pact_version: Link,
-    #[serde(rename = "pb:latest-tagged-pact-version")]
-    pb_latest_tagged_pact_version: Link,
-    #[serde(rename = "pb:previous-distinct")]
-    pb_previous_distinct: Link,
-    #[serde(rename = "pb:diff-previous-distinct")]
-    pb_diff_previous_distinct: Link,
-    #[serde(rename = "pb:diff")]
-    pb_diff: Link,
-    #[serde(rename = "pb:pact-webhooks")]
-    pb_pact_webhooks: Link,
-    #[serde(rename = "pb:consumer-webhooks")]
-    pb_consumer_webhooks: Link,
-    #[serde(rename = "pb:tag-prod-version")]
-    pb_tag_prod_version: Link,
-    #[serde(rename = "pb:tag-version")]
-    pb_tag_version: Link,
-    #[serde(rename = "pb:publish-verification-results")]
-    pb_publish_verification_results: Link,
-    #[serde(rename = "pb:latest-verification-results")]
-    pb_latest_verification_results: Link,
-    #[serde(rename = "pb:triggered-webhooks")]
-    pb_triggered_webhooks: Link,
-    #[serde(rename = "pb:matrix-for-consumer-version")]
-    pb_matrix_for_consumer_version: Link,
-    curies: Vec<Cury>,
+impl Response {
+    /// Path expressions of the body fields checked by a matching rule,
+    /// e.g. `$.content.email`.
+    pub fn matched_fields(&self) -> Vec<&str> {
+        self.matching_rules
+            .iter()
+            .flat_map(|rules| rules.body.iter().flatten())
+            .map(|(expression, _)| expression.as_str())
+            .collect()
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Cury {
-    name: String,
-    #[serde(rename = "href")]
-    href: String,
-    #[serde(rename = "templated")]
-    templated: bool,
+/// HTTP headers, by name.
+pub type Headers = BTreeMap<String, HeaderValue>;
+
+/// Value of a header, several values are allowed since pact specification v4.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum HeaderValue {
+    Single(String),
+    Multiple(Vec<String>),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Link {
-    title: Option<String>,
-    href: String,
-    name: Option<String>,
-    templated: Option<bool>,
+impl HeaderValue {
+    pub fn values(&self) -> Vec<&str> {
+        match self {
+            HeaderValue::Single(value) => vec![value.as_str()],
+            HeaderValue::Multiple(values) => values.iter().map(String::as_str).collect(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Metadata {
-    #[serde(rename = "pactSpecification")]
-    pact_specification: Pact,
+    #[serde(rename = "pactSpecification", alias = "pact-specification")]
+    pub pact_specification: Pact,
+    /// Plugins used by the interactions (v4)
+    pub plugins: Option<Vec<Plugin>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Pact {
     #[serde(rename = "version")]
-    version: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Plugin {
+    pub name: String,
+    pub version: String,
+    pub configuration: Option<serde_json::Map<String, serde_json::Value>>,
 }
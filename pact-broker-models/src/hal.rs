@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// HAL links of a broker resource, by relation, e.g. `self` or `pb:consumer`.
+///
+/// Every relation is optional: a broker may add or omit relations between versions.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Links {
+    /// Compact URIs of the relations, e.g. `pb` for `pb:consumer`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub curies: Vec<Curie>,
+    #[serde(flatten)]
+    pub relations: BTreeMap<String, Relation>,
+}
+
+impl Links {
+    /// The first link of the relation `rel`, given either compact, e.g. `pb:consumer`,
+    /// or expanded with the curies, e.g. `https://broker/doc/consumer?context=pact`.
+    pub fn get(&self, rel: &str) -> Option<&Link> {
+        self.get_all(rel).first()
+    }
+
+    /// All the links of the relation `rel`, see [`Links::get`].
+    pub fn get_all(&self, rel: &str) -> &[Link] {
+        self.relations
+            .get(rel)
+            .or_else(|| {
+                self.relations
+                    .iter()
+                    .find(|(name, _)| self.expand_rel(name) == rel)
+                    .map(|(_, relation)| relation)
+            })
+            .map(Relation::links)
+            .unwrap_or_default()
+    }
+
+    /// Expand a compact relation with the matching curie, e.g. `pb:consumer` into
+    /// `https://broker/doc/consumer?context=pact`. Other relations are returned as is.
+    pub fn expand_rel(&self, rel: &str) -> String {
+        rel.split_once(':')
+            .and_then(|(prefix, reference)| {
+                self.curies
+                    .iter()
+                    .find(|curie| curie.name == prefix)
+                    .map(|curie| curie.expand(reference))
+            })
+            .unwrap_or_else(|| rel.to_owned())
+    }
+}
+
+/// A relation holds either a single link or an array of links.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Relation {
+    One(Link),
+    Many(Vec<Link>),
+}
+
+impl Relation {
+    pub fn links(&self) -> &[Link] {
+        match self {
+            Relation::One(link) => std::slice::from_ref(link),
+            Relation::Many(links) => links,
+        }
+    }
+}
+
+/// Compact URI, whose `href` template documents the relations with its prefix, e.g.
+/// `{"name": "pb", "href": "https://broker/doc/{rel}?context=pact", "templated": true}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Curie {
+    pub name: String,
+    pub href: String,
+    #[serde(default)]
+    pub templated: bool,
+}
+
+impl Curie {
+    /// URI of the relation `reference`, e.g. `consumer` for `pb:consumer`.
+    pub fn expand(&self, reference: &str) -> String {
+        self.href.replace("{rel}", reference)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Link {
+    pub href: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether `href` is a URI template, e.g. `/pacticipants/foo/versions/1/tags/{tag}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub templated: Option<bool>,
+}
+
+impl Link {
+    pub fn is_templated(&self) -> bool {
+        self.templated.unwrap_or(false)
+    }
+
+    /// The link with the variables of its templated `href` replaced by the percent-encoded
+    /// value of `params`, e.g. `{tag}` by the value of `("tag", "prod")`.
+    /// Variables without a value are removed, as specified by RFC 6570.
+    pub fn expand<K, V>(&self, params: &[(K, V)]) -> Link
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut href = String::with_capacity(self.href.len());
+        let mut rest = self.href.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+            href.push_str(&rest[..start]);
+            let variable = &rest[start + 1..end];
+            if let Some((_, value)) = params.iter().find(|(key, _)| key.as_ref() == variable) {
+                href.push_str(&percent_encode(value.as_ref()));
+            }
+            rest = &rest[end + 1..];
+        }
+        href.push_str(rest);
+
+        Link {
+            href,
+            templated: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// Encode all characters but the unreserved ones of RFC 3986.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Link, Links};
+
+    fn links() -> Links {
+        serde_json::from_value(json!({
+            "self": { "href": "https://broker/pacts/provider/p/consumer/c/latest" },
+            "pb:consumer": { "name": "c", "href": "https://broker/pacticipants/c" },
+            "pb:pacts": [
+                { "href": "https://broker/pacts/provider/p/consumer/c/latest" },
+                { "href": "https://broker/pacts/provider/p/consumer/d/latest" }
+            ],
+            "curies": [
+                { "name": "pb", "href": "https://broker/doc/{rel}?context=pact", "templated": true }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn should_get_links_by_compact_or_expanded_relation() {
+        let links = links();
+
+        assert_eq!(
+            links.expand_rel("pb:consumer"),
+            "https://broker/doc/consumer?context=pact"
+        );
+        assert_eq!(links.get("pb:consumer").unwrap().name.as_deref(), Some("c"));
+        assert_eq!(
+            links.get("https://broker/doc/consumer?context=pact"),
+            links.get("pb:consumer")
+        );
+        assert_eq!(links.get_all("pb:pacts").len(), 2);
+        assert!(links.get("pb:provider").is_none());
+    }
+
+    #[test]
+    fn should_expand_templated_href() {
+        let link = Link {
+            href: "https://broker/pacticipants/c/versions/{version}/tags/{tag}".to_owned(),
+            title: None,
+            name: None,
+            templated: Some(true),
+        };
+
+        let expanded = link.expand(&[("version", "1.0.0"), ("tag", "feat/a b")]);
+
+        assert_eq!(
+            expanded.href,
+            "https://broker/pacticipants/c/versions/1.0.0/tags/feat%2Fa%20b"
+        );
+        assert!(!expanded.is_templated());
+    }
+}
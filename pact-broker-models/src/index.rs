@@ -0,0 +1,89 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hal::{Link, Links};
+
+/// Index of the broker, i.e. the resource served on its root,
+/// linking to the resources it supports.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Index {
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl Index {
+    /// The link of the relation `rel`, e.g. `pb:latest-pact-versions`.
+    pub fn relation(&self, rel: &str) -> Option<&Link> {
+        self.links.get(rel)
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.relation(capability.relation()).is_some()
+    }
+
+    /// Features supported by the broker, e.g. PactFlow and old brokers lack some of them.
+    pub fn capabilities(&self) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| self.supports(*capability))
+            .collect()
+    }
+}
+
+/// A feature of the broker, found in its index.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    LatestPacts,
+    Pacticipants,
+    PublishContracts,
+    PactsForVerification,
+    Environments,
+    Webhooks,
+    Integrations,
+    Dashboard,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 8] = [
+        Capability::LatestPacts,
+        Capability::Pacticipants,
+        Capability::PublishContracts,
+        Capability::PactsForVerification,
+        Capability::Environments,
+        Capability::Webhooks,
+        Capability::Integrations,
+        Capability::Dashboard,
+    ];
+
+    /// Relation of the broker index linking to the feature.
+    pub fn relation(&self) -> &'static str {
+        match self {
+            Capability::LatestPacts => "pb:latest-pact-versions",
+            Capability::Pacticipants => "pb:pacticipants",
+            Capability::PublishContracts => "pb:publish-contracts",
+            Capability::PactsForVerification => "pb:provider-pacts-for-verification",
+            Capability::Environments => "pb:environments",
+            Capability::Webhooks => "pb:webhooks",
+            Capability::Integrations => "pb:integrations",
+            Capability::Dashboard => "pb:dashboard",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::LatestPacts => "latest pacts",
+            Capability::Pacticipants => "pacticipants",
+            Capability::PublishContracts => "contracts publication",
+            Capability::PactsForVerification => "pacts for verification",
+            Capability::Environments => "environments",
+            Capability::Webhooks => "webhooks",
+            Capability::Integrations => "integrations",
+            Capability::Dashboard => "dashboard",
+        };
+        f.write_str(name)
+    }
+}
@@ -1,2 +1,8 @@
 pub mod contract;
+pub mod hal;
+pub mod index;
+pub mod matching;
+pub mod pacticipants;
 pub mod pacts;
+pub mod publish;
+pub mod webhooks;
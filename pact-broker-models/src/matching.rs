@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Matching rules of a request, a response or a message, by category.
+///
+/// Rules of the `body`, `header`, `query` and `metadata` categories are keyed by
+/// a path expression, e.g. `$.content.email` for a body field or `Content-Type` for a header.
+/// Pact specification v2 rules, keyed by a single expression such as `$.body.content.email`,
+/// are sorted into their category.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(try_from = "serde_json::Map<String, serde_json::Value>")]
+pub struct MatchingRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<BTreeMap<String, RuleList>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<BTreeMap<String, RuleList>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<BTreeMap<String, RuleList>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<RuleList>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<RuleList>,
+    /// Rules of the message metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, RuleList>>,
+}
+
+/// Matching rules of pact specification v3 and later.
+#[derive(Deserialize)]
+struct Categories {
+    body: Option<BTreeMap<String, RuleList>>,
+    #[serde(alias = "headers")]
+    header: Option<BTreeMap<String, RuleList>>,
+    query: Option<BTreeMap<String, RuleList>>,
+    path: Option<RuleList>,
+    status: Option<RuleList>,
+    metadata: Option<BTreeMap<String, RuleList>>,
+}
+
+impl TryFrom<serde_json::Map<String, serde_json::Value>> for MatchingRules {
+    type Error = serde_json::Error;
+
+    fn try_from(map: serde_json::Map<String, serde_json::Value>) -> Result<Self, Self::Error> {
+        if !map.keys().any(|key| key.starts_with('$')) {
+            let categories: Categories = serde_json::from_value(map.into())?;
+            return Ok(Self {
+                body: categories.body,
+                header: categories.header,
+                query: categories.query,
+                path: categories.path,
+                status: categories.status,
+                metadata: categories.metadata,
+            });
+        }
+
+        let mut rules = Self::default();
+        for (expression, matcher) in map {
+            let rule = RuleList {
+                combine: None,
+                matchers: vec![serde_json::from_value(matcher)?],
+            };
+            let (category, key) = split_v2_expression(&expression);
+            let rules = match category {
+                "body" => rules.body.get_or_insert_with(BTreeMap::new),
+                "headers" | "header" => rules.header.get_or_insert_with(BTreeMap::new),
+                "query" => rules.query.get_or_insert_with(BTreeMap::new),
+                "path" => {
+                    rules.path = Some(rule);
+                    continue;
+                }
+                _ => continue,
+            };
+            rules.insert(key, rule);
+        }
+        Ok(rules)
+    }
+}
+
+/// Split a v2 expression such as `$.body.content.email` into its category and
+/// the expression within the category, i.e. `body` and `$.content.email`.
+fn split_v2_expression(expression: &str) -> (&str, String) {
+    let expression = expression.trim_start_matches("$.");
+    let (category, rest) = match expression.find(['.', '[']) {
+        Some(index) => expression.split_at(index),
+        None => (expression, ""),
+    };
+    let key = match category {
+        "body" => format!("${rest}"),
+        _ => rest.trim_start_matches('.').to_owned(),
+    };
+    (category, key)
+}
+
+/// Matchers applied to an item, combined with a logical `AND` unless stated otherwise.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RuleList {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combine: Option<Combine>,
+    pub matchers: Vec<Matcher>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Combine {
+    And,
+    Or,
+}
+
+/// A matcher, e.g. `{"match": "regex", "regex": "\\d+"}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Matcher {
+    /// Kind of matcher: `type`, `regex`, `equality`, `include`, `integer`, `decimal`,
+    /// `number`, `timestamp`, `date`, `time`, `null`, `boolean`, `contentType`, `values`,
+    /// `arrayContains`, `semver`, `statusCode`, `notEmpty`, `eachKey`, `eachValue`, ...
+    /// Pact specification v2 may omit it for `regex` matchers.
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+    /// Format of `timestamp`, `date` and `time` matchers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Expected value of `include` and `contentType` matchers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// Attributes specific to other matchers
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Generators of a request, a response or a message, by category,
+/// keyed by a path expression like the [`MatchingRules`].
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Generators {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<BTreeMap<String, Generator>>,
+    #[serde(alias = "headers", skip_serializing_if = "Option::is_none")]
+    pub header: Option<BTreeMap<String, Generator>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<BTreeMap<String, Generator>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Generator>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Generator>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, Generator>>,
+}
+
+/// A generator, e.g. `{"type": "Uuid"}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Generator {
+    /// Kind of generator: `RandomInt`, `RandomDecimal`, `RandomHexadecimal`, `RandomString`,
+    /// `Regex`, `Uuid`, `Date`, `Time`, `DateTime`, `RandomBoolean`, `ProviderState`, `MockServerURL`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Attributes of the generator, e.g. `min` and `max` for `RandomInt`
+    #[serde(flatten)]
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Combine, MatchingRules};
+
+    #[test]
+    fn should_read_v3_matching_rules() {
+        let rules: MatchingRules = serde_json::from_value(json!({
+            "body": {
+                "$.content.uuid": {
+                    "combine": "AND",
+                    "matchers": [{ "match": "regex", "regex": "[0-9a-f-]+" }]
+                }
+            },
+            "path": { "matchers": [{ "match": "type" }] }
+        }))
+        .unwrap();
+
+        let uuid = &rules.body.unwrap()["$.content.uuid"];
+        assert_eq!(uuid.combine, Some(Combine::And));
+        assert_eq!(uuid.matchers[0].regex.as_deref(), Some("[0-9a-f-]+"));
+        assert!(rules.path.is_some());
+    }
+
+    #[test]
+    fn should_sort_v2_matching_rules_into_categories() {
+        let rules: MatchingRules = serde_json::from_value(json!({
+            "$.body.content[*].email": { "match": "type", "min": 1 },
+            "$.headers.Content-Type": { "regex": "application/json.*" },
+            "$.query.page": { "match": "integer" },
+            "$.path": { "regex": "/orders/\\d+" }
+        }))
+        .unwrap();
+
+        let email = &rules.body.unwrap()["$.content[*].email"];
+        assert_eq!(email.matchers[0].kind.as_deref(), Some("type"));
+        assert_eq!(email.matchers[0].min, Some(1));
+        assert!(rules.header.unwrap().contains_key("Content-Type"));
+        assert!(rules.query.unwrap().contains_key("page"));
+        assert!(rules.path.is_some());
+    }
+}
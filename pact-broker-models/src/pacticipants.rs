@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::hal::Links;
+
+/// The pacticipants known by the broker.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Pacticipants {
+    #[serde(rename = "_embedded", default)]
+    pub embedded: PacticipantsEmbedded,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct PacticipantsEmbedded {
+    #[serde(default)]
+    pub pacticipants: Vec<Pacticipant>,
+}
+
+/// A consumer or a provider.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Pacticipant {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub created_at: Option<String>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl Pacticipants {
+    pub fn pacticipants(&self) -> &[Pacticipant] {
+        &self.embedded.pacticipants
+    }
+}
+
+/// A version of a pacticipant, i.e. of a consumer or a provider.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    pub number: String,
+    pub build_url: Option<String>,
+    pub created_at: Option<String>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+/// Attributes of a version to create.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NewVersion {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_url: Option<String>,
+}
+
+/// A tag of a pacticipant version, e.g. `prod`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub name: String,
+    pub created_at: Option<String>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
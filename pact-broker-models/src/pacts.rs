@@ -1,40 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+use crate::hal::{self, Links};
+
+/// Latest pacts of the broker, one for each consumer and provider.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Pacts {
+    #[serde(default)]
     pub pacts: Vec<Pact>,
-    #[serde(rename = "_links")]
-    pub links: ResponseLinks,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ResponseLinks {
-    #[serde(rename = "self")]
-    pub links_self: Link,
-    #[serde(rename = "pb:pacts")]
-    pub pb_pacts: Vec<PbPact>,
-    pub curies: Vec<Cury>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Cury {
-    pub name: String,
-    #[serde(rename = "href")]
-    pub href: String,
-    pub templated: bool,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Link {
-    pub href: String,
-    pub title: Option<String>,
-    pub name: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PbPact {
-    pub href: String,
-    pub title: String,
+impl Pacts {
+    /// Links to the latest contract of each pact.
+    pub fn pact_links(&self) -> &[hal::Link] {
+        self.links.get_all("pb:pacts")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,8 +24,15 @@ pub struct Pact {
     pub created_at: String,
     #[serde(rename = "_embedded")]
     pub embedded: PactEmbedded,
-    #[serde(rename = "_links")]
-    pub links: PactLinks,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl Pact {
+    /// Link to the latest contract of the pact.
+    pub fn link(&self) -> Option<&hal::Link> {
+        self.links.get("self")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -58,8 +46,8 @@ pub struct Consumer {
     pub name: String,
     #[serde(rename = "_embedded")]
     pub embedded: ConsumerEmbedded,
-    #[serde(rename = "_links")]
-    pub links: ConsumerLinks,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -70,32 +58,82 @@ pub struct ConsumerEmbedded {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Version {
     pub number: String,
-
-    #[serde(rename = "_links")]
-    pub links: VersionLinks,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct VersionLinks {
-    #[serde(rename = "self")]
-    pub links_self: Link,
+pub struct Provider {
+    pub name: String,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ConsumerLinks {
-    #[serde(rename = "self")]
-    pub links_self: Link,
+/// Versions of the pact between a consumer and a provider.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct PactVersions {
+    #[serde(rename = "_links", default)]
+    pub links: Links,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Provider {
-    pub name: String,
-    #[serde(rename = "_links")]
-    pub links: ConsumerLinks,
+impl PactVersions {
+    /// Links to the contract of each version, the latest first.
+    pub fn versions(&self) -> &[hal::Link] {
+        self.links.get_all("pb:pact-versions")
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PactLinks {
-    #[serde(rename = "self")]
-    pub links_self: Vec<Link>,
+/// Latest pacts of a provider, one for each of its consumers.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ProviderPacts {
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl ProviderPacts {
+    /// Links to the latest contract of each consumer, named after the consumer.
+    pub fn pacts(&self) -> &[hal::Link] {
+        self.links.get_all("pb:pacts")
+    }
+
+    /// Link to the latest contract of `consumer`.
+    pub fn pact(&self, consumer: &str) -> Option<&hal::Link> {
+        self.pacts()
+            .iter()
+            .find(|link| link.name.as_deref() == Some(consumer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Pacts;
+
+    #[test]
+    fn should_read_pacts_without_curies_and_with_single_links() {
+        let pacts: Pacts = serde_json::from_value(json!({
+            "pacts": [{
+                "createdAt": "2023-01-10T10:00:00+00:00",
+                "_embedded": {
+                    "consumer": {
+                        "name": "c",
+                        "_embedded": { "version": { "number": "1.0.0" } }
+                    },
+                    "provider": { "name": "p" }
+                },
+                "_links": { "self": { "href": "https://broker/pacts/provider/p/consumer/c/latest" } }
+            }],
+            "_links": {
+                "pb:pacts": { "href": "https://broker/pacts/provider/p/consumer/c/latest" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(pacts.pact_links().len(), 1);
+        assert_eq!(
+            pacts.pacts[0].link().map(|link| link.href.as_str()),
+            Some("https://broker/pacts/provider/p/consumer/c/latest")
+        );
+    }
 }
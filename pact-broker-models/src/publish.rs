@@ -0,0 +1,145 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::hal::Links;
+
+/// Request of the `/contracts/publish` endpoint, publishing the contracts of a
+/// consumer version, and creating the version with its branch and tags.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishContracts {
+    pub pacticipant_name: String,
+    pub pacticipant_version_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// URL of the CI build which published the contracts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_url: Option<String>,
+    pub contracts: Vec<ContractToPublish>,
+}
+
+impl PublishContracts {
+    pub fn new(pacticipant_name: &str, pacticipant_version_number: &str) -> Self {
+        Self {
+            pacticipant_name: pacticipant_name.to_owned(),
+            pacticipant_version_number: pacticipant_version_number.to_owned(),
+            branch: None,
+            tags: vec![],
+            build_url: None,
+            contracts: vec![],
+        }
+    }
+
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_owned());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_owned());
+        self
+    }
+
+    pub fn with_build_url(mut self, build_url: &str) -> Self {
+        self.build_url = Some(build_url.to_owned());
+        self
+    }
+
+    pub fn with_contract(mut self, contract: ContractToPublish) -> Self {
+        self.contracts.push(contract);
+        self
+    }
+}
+
+/// A contract to publish, its content is base64 encoded.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractToPublish {
+    pub consumer_name: String,
+    pub provider_name: String,
+    /// Kind of contract, `pact` for pact files
+    pub specification: String,
+    pub content_type: String,
+    pub content: String,
+}
+
+impl ContractToPublish {
+    /// The pact file `content`, published as is: only the names of its
+    /// consumer and provider are read from it.
+    pub fn pact(content: &[u8]) -> serde_json::Result<Self> {
+        let pact: PactParties = serde_json::from_slice(content)?;
+        Ok(Self {
+            consumer_name: pact.consumer.name,
+            provider_name: pact.provider.name,
+            specification: "pact".to_owned(),
+            content_type: "application/json".to_owned(),
+            content: STANDARD.encode(content),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct PactParties {
+    consumer: PactParty,
+    provider: PactParty,
+}
+
+#[derive(Deserialize)]
+struct PactParty {
+    name: String,
+}
+
+/// Response of the `/contracts/publish` endpoint.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PublishResults {
+    /// Messages to display to the user, e.g. the URL of the published contracts
+    #[serde(default)]
+    pub notices: Vec<Notice>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Notice {
+    /// Level of the notice: `debug`, `info`, `warning`, `error`, `success` or `prompt`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: String,
+}
+
+/// Result of the verification of a contract by a provider version,
+/// published to the `pb:publish-verification-results` link of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationResult {
+    pub success: bool,
+    pub provider_application_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_results: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_by: Option<VerifiedBy>,
+}
+
+/// Library which verified the contract, e.g. `Pact-Rust` `1.0.0`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VerifiedBy {
+    pub implementation: String,
+    pub version: String,
+}
+
+/// A verification result, as recorded by the broker.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+    pub provider_name: Option<String>,
+    pub provider_application_version: Option<String>,
+    pub success: bool,
+    pub verification_date: Option<String>,
+    pub test_results: Option<serde_json::Value>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
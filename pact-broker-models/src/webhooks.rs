@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contract::Pacticant,
+    hal::{Link, Links},
+};
+
+/// List of webhooks, linking to each webhook.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Webhooks {
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl Webhooks {
+    pub fn webhooks(&self) -> &[Link] {
+        self.links.get_all("pb:webhooks")
+    }
+}
+
+/// A webhook, sending a request when an event happens on the pacts it applies to.
+/// A webhook without consumer or provider applies to all of them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumer: Option<Pacticant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Pacticant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    pub request: WebhookRequest,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(skip_serializing)]
+    pub created_at: Option<String>,
+    #[serde(rename = "_links", default, skip_serializing)]
+    pub links: Links,
+}
+
+impl Webhook {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Name of the webhook: its description, or the request it sends.
+    pub fn name(&self) -> String {
+        match &self.description {
+            Some(description) => description.clone(),
+            None => format!("{} {}", self.request.method, self.request.url),
+        }
+    }
+}
+
+/// Request sent by a webhook.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WebhookRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+/// Event triggering a webhook, e.g. `contract_content_changed` or `provider_verification_failed`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Event {
+    pub name: String,
+}
+
+/// Result of the execution of a webhook, run to test it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Execution {
+    pub success: bool,
+    pub logs: Option<String>,
+    pub request: Option<serde_json::Value>,
+    pub response: Option<serde_json::Value>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+/// Webhooks triggered by the publication of a pact, or of a verification result.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TriggeredWebhooks {
+    #[serde(rename = "_embedded")]
+    pub embedded: TriggeredWebhooksEmbedded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TriggeredWebhooksEmbedded {
+    #[serde(rename = "triggeredWebhooks", default)]
+    pub triggered_webhooks: Vec<TriggeredWebhook>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggeredWebhook {
+    pub name: Option<String>,
+    pub status: TriggeredStatus,
+    pub event_name: Option<String>,
+    pub attempts_made: Option<u32>,
+    pub attempts_remaining: Option<u32>,
+    pub triggered_at: Option<String>,
+    pub last_attempted_at: Option<String>,
+    #[serde(rename = "_links", default)]
+    pub links: Links,
+}
+
+impl TriggeredWebhook {
+    /// Link of the webhook which was triggered.
+    pub fn webhook(&self) -> Option<&Link> {
+        self.links.get("pb:webhook")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggeredStatus {
+    NotRun,
+    Retrying,
+    Success,
+    Failure,
+    #[serde(other)]
+    Unknown,
+}
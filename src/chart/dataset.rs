@@ -1,36 +1,116 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
-use pact_broker_models::contract::Contract;
+use chrono::{DateTime, Utc};
+use pact_broker_models::contract::{Contract, Interaction, Message, State};
 use serde::Serialize;
 
 use log::warn;
 
+use crate::{
+    inventory,
+    stale::{self, PactStatus, VerificationStatus},
+    utils::pattern,
+};
+
+use super::flows::Flow;
+
 #[derive(Debug, Serialize)]
 pub struct Graph {
-    nodes: Vec<Node>,
-    links: Vec<Link>,
+    pub(super) nodes: Vec<Node>,
+    pub(super) links: Vec<Link>,
+    /// interactions between the groups of services, for the Sankey chart
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) flows: Vec<Flow>,
 }
 
 #[derive(Debug, Serialize, Clone)]
-struct Node {
+pub(super) struct Node {
     #[serde(rename = "id")]
-    index: usize,
-    name: String,
-    group: Option<String>,
+    pub(super) index: usize,
+    pub(super) name: String,
+    pub(super) group: Option<String>,
+    /// layer of the layered chart, the consumers above their providers
+    pub(super) layer: usize,
+    /// brokers in which the service takes part in a contract
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    brokers: Vec<String>,
 }
 
 impl Node {
     fn new(index: usize, name: String, group: Option<String>) -> Self {
-        Self { index, name, group }
+        Self {
+            index,
+            name,
+            group,
+            layer: 0,
+            brokers: vec![],
+        }
+    }
+
+    fn tag(&mut self, broker: Option<&str>) {
+        if let Some(broker) = broker {
+            if !self.brokers.iter().any(|b| b == broker) {
+                self.brokers.push(broker.to_owned());
+            }
+        }
     }
 }
 
 #[derive(Debug, Serialize)]
-struct Link {
-    source: usize,
-    target: usize,
+pub(super) struct Link {
+    pub(super) source: usize,
+    pub(super) target: usize,
     #[serde(rename = "type")]
     label: String,
+    kind: LinkKind,
+    /// broker the contract comes from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    broker: Option<String>,
+    /// creation date of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    /// whether the contract is older than the staleness threshold
+    stale: bool,
+    /// latest verification of the contract by the provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<VerificationStatus>,
+    /// whether the link does not go down the layers, e.g. in a dependency cycle
+    pub(super) upward: bool,
+    /// interactions of the contract drawn as the link
+    pub(super) interactions: Vec<InteractionDetails>,
+}
+
+/// What a consumer expects from its provider in an interaction.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(super) struct InteractionDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    provider_states: Vec<String>,
+    /// method of a request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
+    /// path of a request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// status of the response to a request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<i16>,
+}
+
+/// How the consumer depends on the provider.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum LinkKind {
+    /// HTTP request
+    Http,
+    /// message sent on a queue or a topic
+    Message,
+    /// request message answered by response messages, e.g. gRPC
+    SynchronousMessage,
 }
 
 impl Link {
@@ -39,49 +119,350 @@ impl Link {
             source,
             target,
             label,
+            kind: LinkKind::Http,
+            broker: None,
+            created_at: None,
+            stale: false,
+            verification: None,
+            upward: false,
+            interactions: vec![],
         }
     }
+
+    fn with_kind(mut self, kind: LinkKind) -> Self {
+        self.kind = kind;
+        self
+    }
 }
 
-impl From<&Vec<Contract>> for Graph {
-    fn from(responses: &Vec<Contract>) -> Self {
+/// Metadata keys holding the destination of a message, by order of preference.
+const DESTINATION_KEYS: [&str; 7] = [
+    "topic",
+    "kafka_topic",
+    "queue",
+    "destination",
+    "exchange",
+    "routingKey",
+    "channel",
+];
+
+/// Label of a message: its description, and its destination found in the metadata.
+fn message_label(message: &Message) -> String {
+    let description = message.description.as_deref().unwrap_or("message");
+    let destination = message.metadata.as_ref().and_then(|metadata| {
+        DESTINATION_KEYS
+            .iter()
+            .find_map(|key| metadata.get(*key).and_then(|value| value.as_str()))
+    });
+    match destination {
+        Some(destination) => format!("{description} [{destination}]"),
+        None => description.to_owned(),
+    }
+}
+
+/// Label and kind of the link drawn for `interaction`, and the details of the interaction.
+fn interaction_link(interaction: &Interaction) -> (String, LinkKind, InteractionDetails) {
+    match interaction {
+        Interaction::Http(interaction) => {
+            let method = interaction.request.method.to_uppercase();
+            (
+                format!("{} {}", method, interaction.request.route()),
+                LinkKind::Http,
+                InteractionDetails {
+                    description: interaction.description.clone(),
+                    provider_states: inventory::provider_states(interaction).collect(),
+                    method: Some(method),
+                    path: Some(interaction.request.path.clone()),
+                    status: Some(interaction.response.status),
+                },
+            )
+        }
+        Interaction::AsynchronousMessage(message) => message_link(message),
+        Interaction::SynchronousMessage(message) => (
+            message.description.clone().unwrap_or_default(),
+            LinkKind::SynchronousMessage,
+            message_details(&message.description, &message.provider_states),
+        ),
+    }
+}
+
+/// Label and kind of the link drawn for `message`, and the details of the message.
+fn message_link(message: &Message) -> (String, LinkKind, InteractionDetails) {
+    (
+        message_label(message),
+        LinkKind::Message,
+        message_details(&message.description, &message.provider_states),
+    )
+}
+
+fn message_details(
+    description: &Option<String>,
+    states: &Option<Vec<State>>,
+) -> InteractionDetails {
+    InteractionDetails {
+        description: description.clone(),
+        provider_states: states
+            .iter()
+            .flatten()
+            .map(|state| state.name.clone())
+            .collect(),
+        method: None,
+        path: None,
+        status: None,
+    }
+}
+
+impl Graph {
+    /// Build a single graph from the contracts of several brokers.
+    /// Services are de-duplicated by name, nodes and links are tagged with the broker name.
+    pub fn from_brokers<'a, I>(brokers: I) -> Self
+    where
+        I: IntoIterator<Item = (Option<&'a str>, &'a [Contract])>,
+    {
         let mut pacticant = HashMap::<&str, Node>::new();
         let mut links: Vec<Link> = vec![];
 
         let mut index = 0;
-        responses.iter().for_each(|resp| {
-            let provider = pacticant
-                .entry(&resp.provider.name)
-                .or_insert_with(|| {
+        brokers.into_iter().for_each(|(broker, contracts)| {
+            contracts.iter().for_each(|resp| {
+                let provider = pacticant.entry(&resp.provider.name).or_insert_with(|| {
                     index += 1;
                     Node::new(index, resp.provider.name.clone(), None)
-                })
-                .clone();
-            let consumer = pacticant.entry(&resp.consumer.name).or_insert_with(|| {
-                index += 1;
-                Node::new(index, resp.consumer.name.clone(), None)
-            });
-            match &resp.interactions {
-                Some(interactions) => interactions.iter().for_each(|interaction| {
-                    links.push(Link::new(
-                        consumer.index,
-                        provider.index,
-                        format!(
-                            "{} {}",
-                            interaction.request.method.to_uppercase(),
-                            interaction.request.path
-                        ),
-                    ))
-                }),
-                None => warn!(
-                    "No interaction describe for {} and {}",
-                    consumer.name, provider.name
-                ),
-            };
+                });
+                provider.tag(broker);
+                let provider = provider.clone();
+                let consumer = pacticant.entry(&resp.consumer.name).or_insert_with(|| {
+                    index += 1;
+                    Node::new(index, resp.consumer.name.clone(), None)
+                });
+                consumer.tag(broker);
+                // whether the path of the interaction was templated, and its link
+                let mut interactions: Vec<(bool, (String, LinkKind, InteractionDetails))> = resp
+                    .interactions
+                    .iter()
+                    .flatten()
+                    .map(|interaction| {
+                        let templated = interaction
+                            .as_http()
+                            .is_some_and(|http| http.request.route.is_some());
+                        (templated, interaction_link(interaction))
+                    })
+                    .collect();
+                interactions.extend(
+                    resp.messages
+                        .iter()
+                        .flatten()
+                        .map(|message| (false, message_link(message))),
+                );
+                if interactions.is_empty() {
+                    warn!(
+                        "No interaction describe for {} and {}",
+                        consumer.name, provider.name
+                    );
+                }
+                // requests to the same path template share a link
+                let mut contract_links: Vec<Link> = vec![];
+                let mut routes = HashMap::<String, usize>::new();
+                for (templated, (label, kind, details)) in interactions {
+                    match routes.get(&label) {
+                        Some(&position) if templated => {
+                            contract_links[position].interactions.push(details)
+                        }
+                        _ => {
+                            if templated {
+                                routes.insert(label.clone(), contract_links.len());
+                            }
+                            let mut link =
+                                Link::new(consumer.index, provider.index, label).with_kind(kind);
+                            link.broker = broker.map(str::to_owned);
+                            link.created_at = resp.created_at.clone();
+                            link.interactions.push(details);
+                            contract_links.push(link)
+                        }
+                    }
+                }
+                links.extend(contract_links);
+            })
         });
         let mut nodes: Vec<Node> = pacticant.values().cloned().collect();
-        nodes.sort_by(|a, b| a.index.cmp(&b.index));
-        links.sort_by(|a, b| a.source.cmp(&b.source));
-        Self { nodes, links }
+        nodes.sort_by_key(|a| a.index);
+        links.sort_by_key(|a| a.source);
+        Self {
+            nodes,
+            links,
+            flows: vec![],
+        }
+    }
+
+    /// Assign each node to the first group having a pattern matching its name.
+    pub fn with_groups(mut self, groups: &BTreeMap<String, Vec<String>>) -> Self {
+        self.nodes.iter_mut().for_each(|node| {
+            node.group = groups
+                .iter()
+                .find(|(_, patterns)| pattern::matches_any(patterns, &node.name))
+                .map(|(group, _)| group.clone());
+        });
+        self
+    }
+
+    /// Mark the links of the contracts created more than `threshold` before `now` as stale.
+    pub fn with_staleness(mut self, threshold: Duration, now: DateTime<Utc>) -> Self {
+        self.links.iter_mut().for_each(|link| {
+            link.stale = link
+                .created_at
+                .as_deref()
+                .and_then(|created_at| stale::age(created_at, now))
+                .is_some_and(|age| age > threshold);
+        });
+        self
+    }
+
+    /// Set the latest verification of the contract of each link, found in `statuses`.
+    pub fn with_verifications(mut self, statuses: &[PactStatus]) -> Self {
+        let names: HashMap<usize, &str> = self
+            .nodes
+            .iter()
+            .map(|node| (node.index, node.name.as_str()))
+            .collect();
+        let verifications: HashMap<(Option<&str>, &str, &str), VerificationStatus> = statuses
+            .iter()
+            .filter_map(|status| {
+                status.verification.map(|verification| {
+                    (
+                        (
+                            status.broker.as_deref(),
+                            status.consumer.as_str(),
+                            status.provider.as_str(),
+                        ),
+                        verification,
+                    )
+                })
+            })
+            .collect();
+        for link in &mut self.links {
+            if let (Some(consumer), Some(provider)) =
+                (names.get(&link.source), names.get(&link.target))
+            {
+                link.verification = verifications
+                    .get(&(link.broker.as_deref(), *consumer, *provider))
+                    .copied();
+            }
+        }
+        self
+    }
+}
+
+impl From<&Vec<Contract>> for Graph {
+    fn from(responses: &Vec<Contract>) -> Self {
+        Self::from_brokers([(None, responses.as_slice())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use crate::routes::Normalizer;
+
+    use super::{Graph, LinkKind};
+
+    #[test]
+    fn should_draw_message_links() {
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            {
+                "consumer": { "name": "ms.billing" },
+                "provider": { "name": "ms.order" },
+                "messages": [
+                    {
+                        "description": "An order created event",
+                        "contents": { "id": 1 },
+                        "metadata": { "topic": "order.created" }
+                    }
+                ]
+            },
+            {
+                "consumer": { "name": "ms.shipping" },
+                "provider": { "name": "ms.order" },
+                "interactions": [
+                    {
+                        "type": "Asynchronous/Messages",
+                        "description": "An order paid event",
+                        "contents": { "content": { "id": 1 } }
+                    },
+                    {
+                        "description": "A request for an order",
+                        "request": { "method": "get", "path": "/orders/1" },
+                        "response": { "status": 200 }
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let graph = Graph::from(&contracts);
+
+        let links: Vec<(&str, LinkKind)> = graph
+            .links
+            .iter()
+            .map(|link| (link.label.as_str(), link.kind))
+            .collect();
+        assert_eq!(graph.nodes.len(), 3);
+        assert!(links.contains(&("An order created event [order.created]", LinkKind::Message)));
+        assert!(links.contains(&("An order paid event", LinkKind::Message)));
+        assert!(links.contains(&("GET /orders/1", LinkKind::Http)));
+    }
+
+    #[test]
+    fn should_keep_the_interactions_of_a_link() {
+        let mut contracts: Vec<Contract> = serde_json::from_value(json!([
+            {
+                "consumer": { "name": "ms.billing" },
+                "provider": { "name": "ms.order" },
+                "interactions": [
+                    {
+                        "description": "an order",
+                        "providerStates": [{ "name": "an order exists" }],
+                        "request": { "method": "get", "path": "/orders/1" },
+                        "response": { "status": 200 }
+                    },
+                    {
+                        "description": "a missing order",
+                        "request": { "method": "get", "path": "/orders/2" },
+                        "response": { "status": 404 }
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(Graph::from(&contracts).links.len(), 2);
+
+        let normalizer = Normalizer::default();
+        contracts
+            .iter_mut()
+            .for_each(|contract| normalizer.normalize_contract(contract));
+        let graph = Graph::from(&contracts);
+
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!(graph.links[0].label, "GET /orders/{id}");
+        assert_eq!(
+            serde_json::to_value(&graph.links[0].interactions).unwrap(),
+            json!([
+                {
+                    "description": "an order",
+                    "provider_states": ["an order exists"],
+                    "method": "GET",
+                    "path": "/orders/1",
+                    "status": 200
+                },
+                {
+                    "description": "a missing order",
+                    "method": "GET",
+                    "path": "/orders/2",
+                    "status": 404
+                }
+            ])
+        );
     }
 }
@@ -0,0 +1,107 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::dataset::Graph;
+
+/// Interactions of the services of a consumer group with those of a provider group.
+#[derive(Debug, Serialize, PartialEq)]
+pub(super) struct Flow {
+    pub(super) consumer: String,
+    pub(super) provider: String,
+    /// number of interactions, the width of the band
+    pub(super) interactions: usize,
+}
+
+impl Graph {
+    /// Weight the links between groups by their number of interactions.
+    /// A service without group makes up a group of its own, named after it.
+    pub fn with_flows(mut self) -> Self {
+        let groups: HashMap<usize, &str> = self
+            .nodes
+            .iter()
+            .map(|node| (node.index, node.group.as_deref().unwrap_or(&node.name)))
+            .collect();
+        let mut flows = BTreeMap::<(&str, &str), usize>::new();
+        for link in &self.links {
+            if let (Some(consumer), Some(provider)) =
+                (groups.get(&link.source), groups.get(&link.target))
+            {
+                *flows.entry((consumer, provider)).or_default() += link.interactions.len();
+            }
+        }
+        self.flows = flows
+            .into_iter()
+            .map(|((consumer, provider), interactions)| Flow {
+                consumer: consumer.to_owned(),
+                provider: provider.to_owned(),
+                interactions,
+            })
+            .collect();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use super::Flow;
+    use crate::{chart::dataset::Graph, routes::Normalizer};
+
+    #[test]
+    fn should_weight_the_flows_between_groups() {
+        let pact = |consumer: &str, provider: &str, count: usize| {
+            json!({
+                "consumer": { "name": consumer },
+                "provider": { "name": provider },
+                "interactions": (0..count).map(|i| json!({
+                    "description": format!("request {i}"),
+                    "request": { "method": "get", "path": format!("/orders/{i}") },
+                    "response": { "status": 200 }
+                })).collect::<Vec<_>>()
+            })
+        };
+        let mut contracts: Vec<Contract> = serde_json::from_value(json!([
+            pact("front.web", "ms.order", 2),
+            pact("front.app", "ms.order", 1),
+            pact("front.app", "legacy", 3),
+            pact("ms.order", "ms.stock", 1),
+        ]))
+        .unwrap();
+        let groups = BTreeMap::from([
+            ("front".to_owned(), vec!["front.*".to_owned()]),
+            ("ms".to_owned(), vec!["ms.*".to_owned()]),
+        ]);
+
+        // the interactions of a contract share the link of `/orders/{id}`
+        let normalizer = Normalizer::default();
+        contracts
+            .iter_mut()
+            .for_each(|contract| normalizer.normalize_contract(contract));
+
+        let graph = Graph::from(&contracts).with_groups(&groups).with_flows();
+
+        assert_eq!(graph.links.len(), 4);
+
+        let flow = |consumer: &str, provider: &str, interactions| Flow {
+            consumer: consumer.to_owned(),
+            provider: provider.to_owned(),
+            interactions,
+        };
+        assert_eq!(
+            graph.flows,
+            vec![
+                flow("front", "legacy", 3),
+                flow("front", "ms", 3),
+                flow("ms", "ms", 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::dataset::Graph;
+
+impl Graph {
+    /// Lay the services out in layers, consumers above their providers: the services
+    /// consumed by none are on the first layer, the services consuming none on the last one.
+    ///
+    /// Dependency cycles are broken by the links going back to a service being visited
+    /// by a depth-first search, these links and every link which does not go down a layer
+    /// violate the layering and are marked upward.
+    pub fn with_layers(mut self) -> Self {
+        let position: HashMap<usize, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.index, position))
+            .collect();
+        let n = self.nodes.len();
+        let mut providers = vec![BTreeSet::new(); n];
+        for link in &self.links {
+            if let (Some(&source), Some(&target)) =
+                (position.get(&link.source), position.get(&link.target))
+            {
+                providers[source].insert(target);
+            }
+        }
+        let providers: Vec<Vec<usize>> = providers
+            .into_iter()
+            .map(|set| set.into_iter().collect())
+            .collect();
+
+        let back_edges = back_edges(&providers);
+        let layers = layers(&providers, &back_edges);
+
+        for (node, layer) in self.nodes.iter_mut().zip(&layers) {
+            node.layer = *layer;
+        }
+        for link in &mut self.links {
+            if let (Some(&source), Some(&target)) =
+                (position.get(&link.source), position.get(&link.target))
+            {
+                link.upward = layers[target] <= layers[source];
+            }
+        }
+        self
+    }
+}
+
+/// Links from a node to one of its ancestors in a depth-first search, self-links included.
+fn back_edges(adjacency: &[Vec<usize>]) -> BTreeSet<(usize, usize)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        v: usize,
+        adjacency: &[Vec<usize>],
+        state: &mut [State],
+        back_edges: &mut BTreeSet<(usize, usize)>,
+    ) {
+        state[v] = State::Visiting;
+        for &w in &adjacency[v] {
+            match state[w] {
+                State::New => visit(w, adjacency, state, back_edges),
+                State::Visiting => {
+                    back_edges.insert((v, w));
+                }
+                State::Done => (),
+            }
+        }
+        state[v] = State::Done;
+    }
+
+    let n = adjacency.len();
+    let mut state = vec![State::New; n];
+    let mut back_edges = BTreeSet::new();
+    // start from the services consumed by none, then from the remaining cycles
+    let mut consumed = vec![false; n];
+    adjacency
+        .iter()
+        .enumerate()
+        .flat_map(|(v, targets)| targets.iter().filter(move |&&w| w != v))
+        .for_each(|&w| consumed[w] = true);
+    let roots = (0..n).filter(|&v| !consumed[v]).chain(0..n);
+    for v in roots.collect::<Vec<_>>() {
+        if state[v] == State::New {
+            visit(v, adjacency, &mut state, &mut back_edges);
+        }
+    }
+    back_edges
+}
+
+/// Layer of each node, the longest path reaching it without the back edges,
+/// the nodes without successor being pushed down to the last layer.
+fn layers(adjacency: &[Vec<usize>], back_edges: &BTreeSet<(usize, usize)>) -> Vec<usize> {
+    let n = adjacency.len();
+    let forward = |v: usize| {
+        adjacency[v]
+            .iter()
+            .copied()
+            .filter(move |&w| !back_edges.contains(&(v, w)))
+    };
+
+    // Kahn's algorithm on the acyclic graph left
+    let mut incoming = vec![0; n];
+    for v in 0..n {
+        forward(v).for_each(|w| incoming[w] += 1);
+    }
+    let mut queue: Vec<usize> = (0..n).filter(|&v| incoming[v] == 0).collect();
+    let mut layers = vec![0; n];
+    while let Some(v) = queue.pop() {
+        for w in forward(v) {
+            layers[w] = layers[w].max(layers[v] + 1);
+            incoming[w] -= 1;
+            if incoming[w] == 0 {
+                queue.push(w);
+            }
+        }
+    }
+
+    let last = layers.iter().copied().max().unwrap_or(0);
+    for v in 0..n {
+        if forward(v).next().is_none() && adjacency[v].iter().all(|&w| w == v) {
+            layers[v] = last;
+        }
+    }
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use crate::chart::dataset::Graph;
+
+    #[test]
+    fn should_lay_out_the_services() {
+        // front -> gateway -> order -> stock -> gateway, front -> billing
+        let pact = |consumer: &str, provider: &str| {
+            json!({
+                "consumer": { "name": consumer },
+                "provider": { "name": provider },
+                "interactions": [{
+                    "description": "a request",
+                    "request": { "method": "get", "path": "/" },
+                    "response": { "status": 200 }
+                }]
+            })
+        };
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            pact("front", "gateway"),
+            pact("gateway", "order"),
+            pact("order", "stock"),
+            pact("stock", "gateway"),
+            pact("front", "billing"),
+        ]))
+        .unwrap();
+
+        let graph = Graph::from(&contracts).with_layers();
+
+        let layer = |name: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.name == name)
+                .unwrap()
+                .layer
+        };
+        assert_eq!(
+            ["front", "gateway", "order", "stock", "billing"].map(layer),
+            [0, 1, 2, 3, 3]
+        );
+        let upward: Vec<(usize, usize)> = graph
+            .links
+            .iter()
+            .filter(|link| link.upward)
+            .map(|link| (link.source, link.target))
+            .collect();
+        let index = |name: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|node| node.name == name)
+                .unwrap()
+                .index
+        };
+        assert_eq!(upward, vec![(index("stock"), index("gateway"))]);
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use serde::Serialize;
+
+use super::dataset::Graph;
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+/// Metrics of a service, telling how much the other services depend on it.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct NodeMetrics {
+    pub name: String,
+    pub group: Option<String>,
+    /// number of consumers depending on the service
+    pub fan_in: usize,
+    /// number of providers the service depends on
+    pub fan_out: usize,
+    /// number of interactions the service consumes
+    pub consumed: usize,
+    /// number of interactions the service provides
+    pub provided: usize,
+    /// number of shortest dependency paths between two other services going through the service
+    pub betweenness: f64,
+    /// PageRank of the service, its dependents passing their rank on to it
+    pub pagerank: f64,
+    /// length of the longest chain of transitive dependencies starting at the service
+    pub longest_chain: usize,
+}
+
+impl Graph {
+    /// Compute the metrics of every node.
+    /// Services depend on each other along the links, from the consumer to the provider.
+    pub fn metrics(&self) -> Vec<NodeMetrics> {
+        let position: HashMap<usize, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (node.index, position))
+            .collect();
+        let n = self.nodes.len();
+
+        let mut consumed = vec![0; n];
+        let mut provided = vec![0; n];
+        let mut providers = vec![BTreeSet::new(); n];
+        let mut consumers = vec![BTreeSet::new(); n];
+        for link in &self.links {
+            let (Some(&source), Some(&target)) =
+                (position.get(&link.source), position.get(&link.target))
+            else {
+                continue;
+            };
+            consumed[source] += link.interactions.len();
+            provided[target] += link.interactions.len();
+            if source != target {
+                providers[source].insert(target);
+                consumers[target].insert(source);
+            }
+        }
+        let providers: Vec<Vec<usize>> = providers
+            .into_iter()
+            .map(|set| set.into_iter().collect())
+            .collect();
+
+        let betweenness = betweenness(&providers);
+        let pagerank = pagerank(&providers);
+        let longest_chain = longest_chains(&providers);
+
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| NodeMetrics {
+                name: node.name.clone(),
+                group: node.group.clone(),
+                fan_in: consumers[i].len(),
+                fan_out: providers[i].len(),
+                consumed: consumed[i],
+                provided: provided[i],
+                betweenness: betweenness[i],
+                pagerank: pagerank[i],
+                longest_chain: longest_chain[i],
+            })
+            .collect()
+    }
+}
+
+/// Betweenness centrality of a directed and unweighted graph, with the algorithm of Brandes.
+fn betweenness(adjacency: &[Vec<usize>]) -> Vec<f64> {
+    let n = adjacency.len();
+    let mut centrality = vec![0.0; n];
+    for s in 0..n {
+        let mut stack = vec![];
+        let mut predecessors = vec![vec![]; n];
+        let mut paths = vec![0.0; n];
+        let mut distance = vec![-1i64; n];
+        paths[s] = 1.0;
+        distance[s] = 0;
+
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &w in &adjacency[v] {
+                if distance[w] < 0 {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0; n];
+        while let Some(w) = stack.pop() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    centrality
+}
+
+/// PageRank, the rank of the services without dependency being spread over all the services.
+fn pagerank(adjacency: &[Vec<usize>]) -> Vec<f64> {
+    let n = adjacency.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = adjacency
+            .iter()
+            .zip(&rank)
+            .filter(|(targets, _)| targets.is_empty())
+            .map(|(_, rank)| rank)
+            .sum();
+        let base = (1.0 - DAMPING + DAMPING * dangling) / n as f64;
+        let mut next = vec![base; n];
+        for (v, targets) in adjacency.iter().enumerate() {
+            let share = DAMPING * rank[v] / targets.len() as f64;
+            for &w in targets {
+                next[w] += share;
+            }
+        }
+        let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if delta < TOLERANCE {
+            break;
+        }
+    }
+    rank
+}
+
+/// Length of the longest dependency chain starting at each node, cycles being cut.
+fn longest_chains(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    fn visit(
+        v: usize,
+        adjacency: &[Vec<usize>],
+        on_path: &mut [bool],
+        memo: &mut [Option<usize>],
+    ) -> usize {
+        if let Some(length) = memo[v] {
+            return length;
+        }
+        on_path[v] = true;
+        let mut length = 0;
+        for &w in &adjacency[v] {
+            if !on_path[w] {
+                length = length.max(1 + visit(w, adjacency, on_path, memo));
+            }
+        }
+        on_path[v] = false;
+        memo[v] = Some(length);
+        length
+    }
+
+    let n = adjacency.len();
+    let mut on_path = vec![false; n];
+    let mut memo = vec![None; n];
+    (0..n)
+        .map(|v| visit(v, adjacency, &mut on_path, &mut memo))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use crate::{chart::dataset::Graph, routes::Normalizer};
+
+    #[test]
+    fn should_compute_node_metrics() {
+        // front -> gateway -> order -> stock, gateway -> billing
+        let pact = |consumer: &str, provider: &str, count: usize| {
+            json!({
+                "consumer": { "name": consumer },
+                "provider": { "name": provider },
+                "interactions": (0..count).map(|i| json!({
+                    "description": format!("request {i}"),
+                    "request": { "method": "get", "path": format!("/{i}") },
+                    "response": { "status": 200 }
+                })).collect::<Vec<_>>()
+            })
+        };
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            pact("front", "gateway", 2),
+            pact("gateway", "order", 1),
+            pact("gateway", "billing", 1),
+            pact("order", "stock", 3),
+        ]))
+        .unwrap();
+
+        let metrics = Graph::from(&contracts).metrics();
+        let get = |name: &str| metrics.iter().find(|m| m.name == name).unwrap();
+
+        let gateway = get("gateway");
+        assert_eq!((gateway.fan_in, gateway.fan_out), (1, 2));
+        assert_eq!((gateway.consumed, gateway.provided), (2, 2));
+        // front -> order, front -> billing, front -> stock
+        assert_eq!(gateway.betweenness, 3.0);
+        // order lies on gateway -> stock and front -> stock
+        assert_eq!(get("order").betweenness, 2.0);
+        assert_eq!(get("front").longest_chain, 3);
+        assert_eq!(get("stock").longest_chain, 0);
+        assert!(get("stock").pagerank > get("front").pagerank);
+        let total: f64 = metrics.iter().map(|m| m.pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_count_the_interactions_sharing_a_route() {
+        let mut contracts: Vec<Contract> = serde_json::from_value(json!([{
+            "consumer": { "name": "front" },
+            "provider": { "name": "order" },
+            "interactions": (1..=3).map(|i| json!({
+                "description": format!("order {i}"),
+                "request": { "method": "get", "path": format!("/orders/{i}") },
+                "response": { "status": 200 }
+            })).collect::<Vec<_>>()
+        }]))
+        .unwrap();
+        let normalizer = Normalizer::default();
+        contracts
+            .iter_mut()
+            .for_each(|contract| normalizer.normalize_contract(contract));
+
+        let graph = Graph::from(&contracts);
+        let metrics = graph.metrics();
+        let get = |name: &str| metrics.iter().find(|m| m.name == name).unwrap();
+
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!(get("front").consumed, 3);
+        assert_eq!(get("order").provided, 3);
+    }
+}
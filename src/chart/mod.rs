@@ -2,3 +2,6 @@
 // SPDX-License-Identifier: MIT
 
 pub mod dataset;
+pub mod flows;
+pub mod layers;
+pub mod metrics;
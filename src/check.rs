@@ -0,0 +1,96 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use pact_broker_api::client::BrokerClient;
+
+use crate::{
+    orphans::{self, Orphans},
+    CheckArgs, Rule, Settings,
+};
+
+/// Check the hygiene of the brokers against the rules of `args`, every rule by default,
+/// and fail when any is broken.
+pub async fn run(settings: &Settings, clients: &[BrokerClient], args: &CheckArgs) -> Result<()> {
+    let rules = if args.rule.is_empty() {
+        Rule::value_variants().to_vec()
+    } else {
+        args.rule.clone()
+    };
+    let orphans = orphans::fetch(settings, clients).await?;
+
+    let violations = violations(&orphans, &rules);
+    for violation in &violations {
+        println!("{violation}");
+    }
+    if !violations.is_empty() {
+        bail!("{} violation(s) of the broker rules", violations.len());
+    }
+    println!("Every rule is followed");
+    Ok(())
+}
+
+fn violations(orphans: &Orphans, rules: &[Rule]) -> Vec<String> {
+    let mut violations = vec![];
+    for rule in rules {
+        let name = rule
+            .to_possible_value()
+            .map(|value| value.get_name().to_owned());
+        let name = name.unwrap_or_default();
+        let messages: Vec<String> = match rule {
+            Rule::NoContract => orphans
+                .without_contract
+                .iter()
+                .map(|pacticipant| format!("{pacticipant} takes part in no contract"))
+                .collect(),
+            Rule::UnverifiedProvider => orphans
+                .unverified_providers
+                .iter()
+                .map(|provider| format!("{provider} verified none of its pacts"))
+                .collect(),
+            Rule::MissingProvider => orphans
+                .missing_providers
+                .iter()
+                .map(|(consumer, provider)| {
+                    format!("{consumer} depends on {provider}, which is not in the broker")
+                })
+                .collect(),
+            Rule::Disconnected => orphans
+                .disconnected
+                .iter()
+                .map(|group| format!("{} linked to none of the other services", group.join(", ")))
+                .collect(),
+        };
+        violations.extend(
+            messages
+                .into_iter()
+                .map(|message| format!("[{name}] {message}")),
+        );
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::violations;
+    use crate::{orphans::Orphans, Rule};
+
+    #[test]
+    fn should_report_the_violations_of_the_selected_rules() {
+        let orphans = Orphans {
+            without_contract: vec!["ms.unused".to_owned()],
+            unverified_providers: vec!["ms.stock".to_owned()],
+            missing_providers: vec![("ms.gateway".to_owned(), "ms.legacy".to_owned())],
+            disconnected: vec![vec!["ms.batch".to_owned(), "ms.stock".to_owned()]],
+        };
+
+        assert_eq!(
+            violations(&orphans, &[Rule::MissingProvider, Rule::Disconnected]),
+            vec![
+                "[missing-provider] ms.gateway depends on ms.legacy, which is not in the broker",
+                "[disconnected] ms.batch, ms.stock linked to none of the other services",
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::{net::IpAddr, path::PathBuf, time::Duration};
+
 use clap::Parser;
 use lazy_static::lazy_static;
 
@@ -20,10 +22,14 @@ Commit Branch:       {:?}
     );
 }
 
-#[derive(Debug, Clone, clap::ValueEnum, Copy)]
+#[derive(Debug, Clone, clap::ValueEnum, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum GraphChoice {
     Edge,
     Directed,
+    Layered,
+    Matrix,
+    Sankey,
 }
 
 #[derive(Debug, Parser)]
@@ -35,29 +41,206 @@ pub enum GraphChoice {
     long_about,
 )]
 pub struct Cli {
-    /// Pact broker URL
-    #[arg(short = 'b', long)]
-    pub url: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Path of the configuration file [default: pact-graph-network.toml]
+    #[arg(global = true, short, long)]
+    pub config: Option<PathBuf>,
+    /// Name of the broker profile of the configuration file, repeat it to merge several brokers
+    #[arg(
+        global = true,
+        long,
+        env = "PACT_NETWORK_PROFILE",
+        value_delimiter = ','
+    )]
+    pub profile: Vec<String>,
+    /// Pact broker URL, repeat it to merge several brokers
+    #[arg(global = true, short = 'b', long)]
+    pub url: Vec<String>,
+    /// Directory of pact files written by the consumer tests, read in addition to the brokers
+    #[arg(global = true, long)]
+    pub pact_dir: Vec<PathBuf>,
+    /// Path template of the requests, e.g. `/orders/{id}`, repeat it for several templates
+    #[arg(global = true, long)]
+    pub route: Vec<String>,
+    /// OpenAPI document of a provider, whose paths are used as templates, e.g. `ms.order=openapi.yaml`
+    #[arg(global = true, long, value_parser = parse_openapi)]
+    pub openapi: Vec<(String, PathBuf)>,
+    /// Replace the numeric, UUID and hexadecimal segments of the paths by `{id}`
+    #[arg(global = true, long)]
+    pub normalize_paths: bool,
+    /// Age of a pact from which it is stale, e.g. `30days` [default: 90days]
+    #[arg(global = true, long)]
+    pub stale_after: Option<String>,
+    /// Render the charts again whenever a pact file of `--pact-dir` changes
+    #[arg(global = true, long)]
+    pub watch: bool,
     /// Pact broker username
-    #[arg(short, long)]
+    #[arg(global = true, short, long)]
     pub username: Option<String>,
     /// Pact broker password
-    #[arg(short, long)]
+    #[arg(global = true, short, long)]
     pub password: Option<String>,
     /// Pact broker token
-    #[arg(short, long)]
+    #[arg(global = true, short, long)]
     pub token: Option<String>,
-    /// Path of the output dir
-    #[arg(short, long, default_value = "report")]
-    pub output: String,
+    /// Command printing a Pact broker token, e.g. `vault read -field=token secret/pact`
+    #[arg(global = true, long, conflicts_with = "token")]
+    pub token_command: Option<String>,
+    /// OAuth2 token endpoint used to get a token with the client credentials grant
+    #[arg(global = true, long, requires_all = ["client_id", "client_secret"], conflicts_with_all = ["token", "token_command"])]
+    pub oauth2_token_url: Option<String>,
+    /// OAuth2 client id
+    #[arg(global = true, long, requires = "oauth2_token_url")]
+    pub client_id: Option<String>,
+    /// OAuth2 client secret
+    #[arg(
+        global = true,
+        long,
+        env = "PACT_BROKER_CLIENT_SECRET",
+        hide_env_values = true
+    )]
+    pub client_secret: Option<String>,
+    /// OAuth2 scope requested with the token
+    #[arg(global = true, long, requires = "oauth2_token_url")]
+    pub scope: Option<String>,
+    /// Path of the output dir [default: report]
+    #[arg(global = true, short, long)]
+    pub output: Option<String>,
     // #[clap(short, long, parse(from_occurrences))]
     // verbosity: usize,
-    #[arg(short, long, value_enum, default_value = "edge")]
-    pub graph: GraphChoice,
-    /// timeout of http request in milliseconds
-    #[arg(long, default_value = "2000")]
-    pub timeout: u16,
+    /// [default: edge]
+    #[arg(global = true, short, long, value_enum)]
+    pub graph: Option<GraphChoice>,
+    /// timeout of http request in milliseconds [default: 2000]
+    #[arg(global = true, long)]
+    pub timeout: Option<u16>,
     /// list of service to exclude
-    #[arg(long)]
+    #[arg(global = true, long)]
     pub exclude: Option<Vec<String>>,
 }
+
+/// Parse `provider=path`.
+fn parse_openapi(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((provider, path)) if !provider.is_empty() && !path.is_empty() => {
+            Ok((provider.to_owned(), PathBuf::from(path)))
+        }
+        _ => Err(format!("`{value}` is not of the form `provider=path`")),
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Serve the charts and the JSON graph over HTTP, fetching the contracts periodically
+    Serve(ServeArgs),
+    /// Show the features supported by each broker, found in its index
+    Info,
+    /// Show, for each pact, the webhooks it triggers and whether their last executions succeeded
+    Webhooks,
+    /// List the versions of a pact, and the interactions added and removed by each version
+    History(HistoryArgs),
+    /// Compute the fan-in, fan-out, centrality and longest dependency chain of each service
+    Metrics(MetricsArgs),
+    /// List the endpoints of each provider used by the consumers, with the expected statuses and provider states
+    Endpoints(InventoryArgs),
+    /// Compare the requests of the consumers with the OpenAPI document of each provider given with `--openapi`
+    Coverage(CoverageArgs),
+    /// List the pacts older than `--stale-after`, the consumers which stopped publishing, and the unverified pacts
+    Stale,
+    /// Check the hygiene of the brokers, failing when a rule is broken
+    Check(CheckArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CheckArgs {
+    /// Rule to check, repeat it for several rules [default: every rule]
+    #[arg(long, value_enum)]
+    pub rule: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Rule {
+    /// every pacticipant takes part in a contract
+    NoContract,
+    /// every provider verified one of its pacts
+    UnverifiedProvider,
+    /// the provider of every pact is a pacticipant of the broker
+    MissingProvider,
+    /// the services are all linked together
+    Disconnected,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CoverageArgs {
+    /// Fail when a request is missing from the OpenAPI document of its provider
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct InventoryArgs {
+    /// Format of the inventory, the HTML page is written in the output directory
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: InventoryFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum InventoryFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct MetricsArgs {
+    /// Format of the table, the HTML page is written in the output directory
+    #[arg(long, value_enum, default_value = "console")]
+    pub format: MetricsFormat,
+    /// Column to sort the table by, numbers in descending order
+    #[arg(long, value_enum, default_value = "pagerank")]
+    pub sort: MetricsColumn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MetricsFormat {
+    Console,
+    Csv,
+    Json,
+    Html,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MetricsColumn {
+    Name,
+    FanIn,
+    FanOut,
+    Consumed,
+    Provided,
+    Betweenness,
+    Pagerank,
+    LongestChain,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct HistoryArgs {
+    /// Name of the consumer
+    #[arg(long)]
+    pub consumer: String,
+    /// Name of the provider
+    #[arg(long)]
+    pub provider: String,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub bind: IpAddr,
+    /// Port to listen on
+    #[arg(long, default_value = "8080")]
+    pub port: u16,
+    /// Interval between two fetches of the contracts, e.g. `30s`, `10m`
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    pub refresh: Duration,
+}
@@ -0,0 +1,555 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, info};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::{Cli, GraphChoice};
+
+/// Name of the configuration file, looked up in the working directory
+/// and in the user configuration directory.
+pub const CONFIG_FILE: &str = "pact-graph-network.toml";
+
+const DEFAULT_OUTPUT: &str = "report";
+const DEFAULT_TIMEOUT: u64 = 2000;
+const DEFAULT_STALE_AFTER: &str = "90days";
+
+/// Content of a `pact-graph-network.toml` file.
+///
+/// Top level keys are shared by every profile, a profile overrides them.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Profile used when `--profile` is not given
+    pub default_profile: Option<String>,
+    #[serde(flatten)]
+    pub defaults: Profile,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings of a broker, every key is optional.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+    pub token_command: Option<String>,
+    pub oauth2: Option<OAuth2>,
+    /// timeout of http request in milliseconds
+    pub timeout: Option<u64>,
+    pub exclude: Option<Vec<String>>,
+    /// group name to the list of service patterns belonging to it
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+    pub output: Option<String>,
+    pub graph: Option<GraphChoice>,
+    /// directories of pact files written by the consumer tests
+    pub pact_dirs: Option<Vec<PathBuf>>,
+    /// path templates of the requests, e.g. `/orders/{id}`
+    pub routes: Option<Vec<String>>,
+    /// provider name to the path of its OpenAPI document
+    pub openapi: Option<BTreeMap<String, PathBuf>>,
+    /// whether to replace the identifiers of the paths by `{id}`
+    pub normalize_paths: Option<bool>,
+    /// age of a pact from which it is stale, e.g. `90days`
+    pub stale_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OAuth2 {
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: Option<String>,
+}
+
+/// Credentials used to authenticate against the broker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Credentials {
+    Basic { username: String, password: String },
+    Token(String),
+    TokenCommand(String),
+    OAuth2(OAuth2),
+}
+
+/// A broker to fetch the contracts from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    /// Name of the profile, or host, port and path of the broker, used to tag nodes and links
+    pub name: String,
+    pub url: String,
+    pub credentials: Option<Credentials>,
+    pub timeout: Duration,
+}
+
+/// Settings resolved from the command line, the selected profiles and the defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub sources: Vec<Source>,
+    pub pact_dirs: Vec<PathBuf>,
+    pub exclude: Vec<String>,
+    pub groups: BTreeMap<String, Vec<String>>,
+    pub routes: Vec<String>,
+    pub openapi: BTreeMap<String, PathBuf>,
+    pub normalize_paths: bool,
+    pub stale_after: Duration,
+    pub output: PathBuf,
+    pub graph: GraphChoice,
+}
+
+impl Config {
+    /// Load the configuration from `path`, or merge the user and the project configuration files.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            return Self::read(path);
+        }
+
+        let mut config = Self::default();
+        let candidates = [
+            user_config_dir().map(|dir| dir.join(CONFIG_FILE)),
+            Some(PathBuf::from(CONFIG_FILE)),
+        ];
+        for path in candidates.into_iter().flatten() {
+            if path.is_file() {
+                config = config.merge(Self::read(&path)?);
+            } else {
+                debug!("No configuration file at {}", path.display());
+            }
+        }
+        Ok(config)
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        info!("Configuration: {}", path.display());
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read configuration file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration file {}", path.display()))
+    }
+
+    /// Merge `other` on top of `self`, profiles are merged by name.
+    fn merge(mut self, other: Self) -> Self {
+        self.default_profile = other.default_profile.or(self.default_profile);
+        self.defaults = self.defaults.merge(other.defaults);
+        for (name, profile) in other.profiles {
+            let merged = match self.profiles.remove(&name) {
+                Some(base) => base.merge(profile),
+                None => profile,
+            };
+            self.profiles.insert(name, merged);
+        }
+        self
+    }
+
+    /// Return the settings of the profile `name`, or of the default profile.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        match name.or(self.default_profile.as_deref()) {
+            Some(name) => match self.profiles.get(name) {
+                Some(profile) => Ok(self.defaults.clone().merge(profile.clone())),
+                None => Err(anyhow!(
+                    "Unknown profile `{}`, available profiles: {}",
+                    name,
+                    self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+                )),
+            },
+            None => Ok(self.defaults.clone()),
+        }
+    }
+}
+
+impl Profile {
+    /// Merge `other` on top of `self`.
+    /// Credentials are never mixed: when `other` has complete ones, they replace the ones
+    /// of `self`, otherwise only the credential fields given by `other` are overridden.
+    fn merge(self, other: Self) -> Self {
+        let (username, password, token, token_command, oauth2) = if other.credentials().is_some() {
+            (
+                other.username,
+                other.password,
+                other.token,
+                other.token_command,
+                other.oauth2,
+            )
+        } else {
+            (
+                other.username.or(self.username),
+                other.password.or(self.password),
+                other.token.or(self.token),
+                other.token_command.or(self.token_command),
+                other.oauth2.or(self.oauth2),
+            )
+        };
+        Self {
+            url: other.url.or(self.url),
+            username,
+            password,
+            token,
+            token_command,
+            oauth2,
+            timeout: other.timeout.or(self.timeout),
+            exclude: other.exclude.or(self.exclude),
+            groups: other.groups.or(self.groups),
+            output: other.output.or(self.output),
+            graph: other.graph.or(self.graph),
+            pact_dirs: other.pact_dirs.or(self.pact_dirs),
+            routes: other.routes.or(self.routes),
+            openapi: other.openapi.or(self.openapi),
+            normalize_paths: other.normalize_paths.or(self.normalize_paths),
+            stale_after: other.stale_after.or(self.stale_after),
+        }
+    }
+
+    fn credentials(&self) -> Option<Credentials> {
+        if let Some(oauth2) = &self.oauth2 {
+            return Some(Credentials::OAuth2(oauth2.clone()));
+        }
+        if let Some(command) = &self.token_command {
+            return Some(Credentials::TokenCommand(command.clone()));
+        }
+        if let Some(token) = &self.token {
+            return Some(Credentials::Token(token.clone()));
+        }
+        match (&self.username, &self.password) {
+            (Some(username), Some(password)) => Some(Credentials::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Cli> for Profile {
+    fn from(cli: &Cli) -> Self {
+        let oauth2 = match (&cli.oauth2_token_url, &cli.client_id, &cli.client_secret) {
+            (Some(token_url), Some(client_id), Some(client_secret)) => Some(OAuth2 {
+                token_url: token_url.clone(),
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+                scope: cli.scope.clone(),
+            }),
+            _ => None,
+        };
+        Self {
+            url: None,
+            username: cli.username.clone(),
+            password: cli.password.clone(),
+            token: cli.token.clone(),
+            token_command: cli.token_command.clone(),
+            oauth2,
+            timeout: cli.timeout.map(u64::from),
+            exclude: cli.exclude.clone(),
+            groups: None,
+            output: cli.output.clone(),
+            graph: cli.graph,
+            pact_dirs: (!cli.pact_dir.is_empty()).then(|| cli.pact_dir.clone()),
+            routes: (!cli.route.is_empty()).then(|| cli.route.clone()),
+            openapi: (!cli.openapi.is_empty()).then(|| cli.openapi.iter().cloned().collect()),
+            normalize_paths: cli.normalize_paths.then_some(true),
+            stale_after: cli.stale_after.clone(),
+        }
+    }
+}
+
+impl Settings {
+    /// Resolve the settings of `cli`, the command line taking precedence over the configuration.
+    pub fn load(cli: &Cli) -> Result<Self> {
+        let config = Config::load(cli.config.as_deref())?;
+        Self::resolve(&config, cli)
+    }
+
+    /// Every profile given with `--profile` is a source, as well as every `--url` which uses
+    /// the default profile with the command line on top. Without any, the default profile is used.
+    fn resolve(config: &Config, cli: &Cli) -> Result<Self> {
+        let overlay = Profile::from(cli);
+        let mut profiles = if cli.profile.is_empty() {
+            vec![(None, config.profile(None)?.merge(overlay.clone()))]
+        } else {
+            cli.profile
+                .iter()
+                .map(|name| {
+                    let profile = config.profile(Some(name))?.merge(overlay.clone());
+                    Ok((Some(name.clone()), profile))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let mut pact_dirs: Vec<PathBuf> = vec![];
+        for (_, profile) in &profiles {
+            for dir in profile.pact_dirs.iter().flatten() {
+                if !pact_dirs.contains(dir) {
+                    pact_dirs.push(dir.clone());
+                }
+            }
+        }
+
+        let mut sources = vec![];
+        if !cli.profile.is_empty() || cli.url.is_empty() {
+            for (name, profile) in &profiles {
+                let Some(url) = &profile.url else {
+                    // a profile may only read local pact files
+                    if profile.pact_dirs.is_some() {
+                        continue;
+                    }
+                    bail!(
+                        "The Pact broker URL is missing, use `--url` or set `url` in {CONFIG_FILE}"
+                    );
+                };
+                sources.push(Source::new(name.clone(), url, profile));
+            }
+        }
+        let url_profile = config.profile(None)?.merge(overlay);
+        for url in &cli.url {
+            sources.push(Source::new(None, url, &url_profile));
+        }
+
+        let (_, profile) = profiles.swap_remove(0);
+        let stale_after = profile
+            .stale_after
+            .as_deref()
+            .unwrap_or(DEFAULT_STALE_AFTER);
+        let stale_after = humantime::parse_duration(stale_after)
+            .with_context(|| format!("Invalid duration `{stale_after}` of `stale-after`"))?;
+        Ok(Self {
+            sources,
+            pact_dirs,
+            exclude: profile.exclude.unwrap_or_default(),
+            groups: profile.groups.unwrap_or_default(),
+            routes: profile.routes.unwrap_or_default(),
+            openapi: profile.openapi.unwrap_or_default(),
+            normalize_paths: profile.normalize_paths.unwrap_or_default(),
+            stale_after,
+            output: PathBuf::from(profile.output.as_deref().unwrap_or(DEFAULT_OUTPUT)),
+            graph: profile.graph.unwrap_or(GraphChoice::Edge),
+        })
+    }
+}
+
+impl Source {
+    fn new(name: Option<String>, url: &str, profile: &Profile) -> Self {
+        let name = name.unwrap_or_else(|| match Url::parse(url) {
+            Ok(parsed) => match parsed.host_str() {
+                Some(host) => {
+                    let port = parsed.port().map(|port| format!(":{port}"));
+                    let path = parsed.path().trim_end_matches('/');
+                    format!("{host}{}{path}", port.unwrap_or_default())
+                }
+                None => url.to_owned(),
+            },
+            Err(_) => url.to_owned(),
+        });
+        Self {
+            name,
+            url: url.to_owned(),
+            credentials: profile.credentials(),
+            timeout: Duration::from_millis(profile.timeout.unwrap_or(DEFAULT_TIMEOUT)),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config`.
+fn user_config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::{Config, Credentials, Settings};
+    use crate::{Cli, GraphChoice};
+
+    const CONFIG: &str = r#"
+default-profile = "production"
+timeout = 5000
+exclude = ["ms.legacy-*"]
+
+[groups]
+checkout = ["ms.cart*", "ms.payment*"]
+
+[profiles.production]
+url = "https://pact-broker.example.com"
+token = "t0k3n"
+
+[profiles.staging]
+url = "https://pact-broker.staging.example.com"
+graph = "directed"
+
+[profiles.staging.oauth2]
+token-url = "https://sso.example.com/oauth2/token"
+client-id = "graph"
+client-secret = "s3cr3t"
+"#;
+
+    fn resolve(args: &[&str]) -> Settings {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let cli = Cli::parse_from(["pact-graph-network"].iter().chain(args));
+        Settings::resolve(&config, &cli).unwrap()
+    }
+
+    #[test]
+    fn should_use_the_default_profile() {
+        let settings = resolve(&[]);
+        let source = &settings.sources[0];
+        assert_eq!(source.name, "pact-broker.example.com");
+        assert_eq!(source.url, "https://pact-broker.example.com");
+        assert_eq!(
+            source.credentials,
+            Some(Credentials::Token("t0k3n".to_owned()))
+        );
+        assert_eq!(source.timeout.as_millis(), 5000);
+        assert_eq!(settings.exclude, vec!["ms.legacy-*"]);
+        assert_eq!(settings.groups["checkout"].len(), 2);
+        assert!(matches!(settings.graph, GraphChoice::Edge));
+    }
+
+    #[test]
+    fn should_select_a_profile() {
+        let settings = resolve(&["--profile", "staging"]);
+        let source = &settings.sources[0];
+        assert_eq!(source.name, "staging");
+        assert_eq!(source.url, "https://pact-broker.staging.example.com");
+        assert!(matches!(source.credentials, Some(Credentials::OAuth2(_))));
+        assert!(matches!(settings.graph, GraphChoice::Directed));
+    }
+
+    #[test]
+    fn should_merge_several_brokers() {
+        let settings = resolve(&[
+            "--profile",
+            "staging",
+            "--profile",
+            "production",
+            "--url",
+            "http://localhost:9292",
+        ]);
+        let names: Vec<&str> = settings.sources.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["staging", "production", "localhost:9292"]);
+        assert_eq!(
+            settings.sources[2].credentials,
+            Some(Credentials::Token("t0k3n".to_owned()))
+        );
+    }
+
+    #[test]
+    fn should_authenticate_a_url_with_the_default_profile() {
+        let settings = resolve(&["--url", "http://localhost:9292"]);
+        assert_eq!(settings.sources.len(), 1);
+        let source = &settings.sources[0];
+        assert_eq!(source.url, "http://localhost:9292");
+        assert_eq!(
+            source.credentials,
+            Some(Credentials::Token("t0k3n".to_owned()))
+        );
+        assert_eq!(source.timeout.as_millis(), 5000);
+    }
+
+    #[test]
+    fn should_name_the_brokers_of_a_host_apart() {
+        let settings = resolve(&[
+            "--url",
+            "http://localhost:9292",
+            "--url",
+            "http://localhost:9393/broker/",
+            "--token",
+            "cli-t0k3n",
+        ]);
+        let names: Vec<&str> = settings.sources.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["localhost:9292", "localhost:9393/broker"]);
+        assert!(settings
+            .sources
+            .iter()
+            .all(|source| source.credentials == Some(Credentials::Token("cli-t0k3n".to_owned()))));
+    }
+
+    #[test]
+    fn should_prefer_command_line_arguments() {
+        let settings = resolve(&[
+            "--url",
+            "http://localhost",
+            "-u",
+            "john",
+            "-p",
+            "secret",
+            "--timeout",
+            "100",
+        ]);
+        assert_eq!(settings.sources.len(), 1);
+        let source = &settings.sources[0];
+        assert_eq!(source.url, "http://localhost");
+        assert_eq!(
+            source.credentials,
+            Some(Credentials::Basic {
+                username: "john".to_owned(),
+                password: "secret".to_owned()
+            })
+        );
+        assert_eq!(source.timeout.as_millis(), 100);
+    }
+
+    #[test]
+    fn should_only_override_the_credential_fields_given() {
+        let config: Config = toml::from_str(
+            r#"
+url = "https://pact-broker.example.com"
+username = "graph"
+password = "s3cr3t"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["pact-graph-network", "-u", "john"]);
+        let settings = Settings::resolve(&config, &cli).unwrap();
+        assert_eq!(
+            settings.sources[0].credentials,
+            Some(Credentials::Basic {
+                username: "john".to_owned(),
+                password: "s3cr3t".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_keys() {
+        assert!(toml::from_str::<Config>("timout = 5000").is_err());
+        assert!(
+            toml::from_str::<Config>("[profiles.qa]\nurl = \"http://qa\"\ntokn = \"t\"").is_err()
+        );
+        assert!(toml::from_str::<Config>(CONFIG).is_ok());
+    }
+
+    #[test]
+    fn should_parse_the_stale_duration() {
+        assert_eq!(resolve(&[]).stale_after.as_secs(), 90 * 86400);
+        let settings = resolve(&["--stale-after", "2weeks"]);
+        assert_eq!(settings.stale_after.as_secs(), 14 * 86400);
+    }
+
+    #[test]
+    fn should_read_pact_dirs_without_broker() {
+        let config = Config::default();
+        let cli = Cli::parse_from(["pact-graph-network", "--pact-dir", "pacts"]);
+        let settings = Settings::resolve(&config, &cli).unwrap();
+        assert!(settings.sources.is_empty());
+        assert_eq!(settings.pact_dirs, vec![std::path::PathBuf::from("pacts")]);
+    }
+
+    #[test]
+    fn should_fail_on_unknown_profile() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        assert!(config.profile(Some("qa")).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use pact_broker_api::client::BrokerClient;
+
+use crate::{
+    fetch_sources,
+    inventory::{Endpoint, Inventory},
+    openapi::OpenApi,
+    routes, CoverageArgs, Settings,
+};
+
+/// Differences between the OpenAPI document of a provider and the requests of its consumers.
+#[derive(Debug, Default, PartialEq)]
+struct Comparison {
+    provider: String,
+    /// operations of the document which no consumer requests, e.g. `DELETE /orders/{id}`
+    unused: Vec<String>,
+    /// requests to an operation missing from the document, with their consumers
+    undocumented: Vec<(String, Vec<String>)>,
+    /// requests to a deprecated operation, with their consumers
+    deprecated: Vec<(String, Vec<String>)>,
+}
+
+impl Comparison {
+    fn new(provider: &str, spec: &OpenApi, endpoints: &[Endpoint]) -> Self {
+        let operations = spec.operations();
+        let matches = |method: &str, path: &str, endpoint: &Endpoint| {
+            endpoint.method == method && routes::same_route(path, &endpoint.path)
+        };
+        let consumers = |endpoint: &Endpoint| endpoint.consumers.iter().cloned().collect();
+
+        let mut comparison = Self {
+            provider: provider.to_owned(),
+            ..Self::default()
+        };
+        for (method, path, operation) in &operations {
+            if !endpoints
+                .iter()
+                .any(|endpoint| matches(method, path, endpoint))
+            {
+                comparison.unused.push(match &operation.operation_id {
+                    Some(id) => format!("{method} {path} ({id})"),
+                    None => format!("{method} {path}"),
+                });
+            }
+        }
+        for endpoint in endpoints {
+            let signature = format!("{} {}", endpoint.method, endpoint.path);
+            match operations
+                .iter()
+                .find(|(method, path, _)| matches(method, path, endpoint))
+            {
+                None => comparison
+                    .undocumented
+                    .push((signature, consumers(endpoint))),
+                Some((_, _, operation)) if operation.deprecated => {
+                    comparison.deprecated.push((signature, consumers(endpoint)))
+                }
+                Some(_) => (),
+            }
+        }
+        comparison
+    }
+}
+
+/// Compare the requests of the consumers with the OpenAPI document of each provider
+/// given with `--openapi`, and print the unused operations and the undocumented requests.
+pub async fn report(
+    settings: &Settings,
+    clients: &[BrokerClient],
+    args: &CoverageArgs,
+) -> Result<()> {
+    if settings.openapi.is_empty() {
+        bail!("No OpenAPI document to compare with, use `--openapi provider=path`");
+    }
+    let sources = fetch_sources(settings, clients).await?;
+    let inventory = Inventory::from_contracts(sources.iter().flat_map(|source| &source.contracts));
+
+    let mut comparisons = vec![];
+    for (provider, path) in &settings.openapi {
+        let spec = OpenApi::read(path)?;
+        let endpoints = inventory
+            .providers
+            .iter()
+            .find(|candidate| &candidate.name == provider)
+            .map(|candidate| candidate.endpoints.as_slice())
+            .unwrap_or_default();
+        comparisons.push(Comparison::new(provider, &spec, endpoints));
+    }
+    print!("{}", render(&comparisons));
+
+    let undocumented: usize = comparisons.iter().map(|c| c.undocumented.len()).sum();
+    if args.strict && undocumented > 0 {
+        bail!("{undocumented} request(s) missing from the OpenAPI documents");
+    }
+    Ok(())
+}
+
+fn render(comparisons: &[Comparison]) -> String {
+    let mut out = String::new();
+    for comparison in comparisons {
+        let _ = writeln!(out, "{}", comparison.provider);
+        if comparison.unused.is_empty()
+            && comparison.undocumented.is_empty()
+            && comparison.deprecated.is_empty()
+        {
+            out.push_str("  every operation is requested, every request is documented\n");
+        }
+        if !comparison.unused.is_empty() {
+            out.push_str("  unused operations, requested by no consumer:\n");
+            for operation in &comparison.unused {
+                let _ = writeln!(out, "    {operation}");
+            }
+        }
+        for (title, requests) in [
+            (
+                "undocumented requests, missing from the OpenAPI document:",
+                &comparison.undocumented,
+            ),
+            ("requests to deprecated operations:", &comparison.deprecated),
+        ] {
+            if requests.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "  {title}");
+            for (request, consumers) in requests {
+                let _ = writeln!(out, "    {request} ({})", consumers.join(", "));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use super::{render, Comparison};
+    use crate::{inventory::Inventory, openapi::OpenApi, routes::Normalizer};
+
+    #[test]
+    fn should_find_unused_and_undocumented_operations() {
+        let spec: OpenApi = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+paths:
+  /orders:
+    get: {}
+    post: {}
+  /orders/{orderId}:
+    parameters: []
+    get:
+      operationId: getOrder
+      deprecated: true
+    delete:
+      operationId: deleteOrder
+"#,
+        )
+        .unwrap();
+        let mut contracts: Vec<Contract> = serde_json::from_value(json!([{
+            "consumer": { "name": "ms.billing" },
+            "provider": { "name": "ms.order" },
+            "interactions": [
+                {
+                    "description": "an order",
+                    "request": { "method": "get", "path": "/orders/1" },
+                    "response": { "status": 200 }
+                },
+                {
+                    "description": "the lines of an order",
+                    "request": { "method": "get", "path": "/orders/1/lines" },
+                    "response": { "status": 200 }
+                },
+                {
+                    "description": "a new order",
+                    "request": { "method": "post", "path": "/orders" },
+                    "response": { "status": 201 }
+                }
+            ]
+        }]))
+        .unwrap();
+        let normalizer = Normalizer::default().with_provider("ms.order", spec.routes());
+        contracts
+            .iter_mut()
+            .for_each(|contract| normalizer.normalize_contract(contract));
+        let inventory = Inventory::from_contracts(&contracts);
+
+        let comparison = Comparison::new("ms.order", &spec, &inventory.providers[0].endpoints);
+
+        assert_eq!(
+            render(&[comparison]),
+            "ms.order\n\
+             \x20 unused operations, requested by no consumer:\n\
+             \x20   GET /orders\n\
+             \x20   DELETE /orders/{orderId} (deleteOrder)\n\
+             \x20 undocumented requests, missing from the OpenAPI document:\n\
+             \x20   GET /orders/{id}/lines (ms.billing)\n\
+             \x20 requests to deprecated operations:\n\
+             \x20   GET /orders/{orderId} (ms.billing)\n"
+        );
+    }
+
+    #[test]
+    fn should_prefix_the_paths_with_the_base_path() {
+        let spec: OpenApi = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+servers:
+  - url: https://orders.example.com/api/v1/
+paths:
+  /orders/{orderId}:
+    get: {}
+"#,
+        )
+        .unwrap();
+        let mut contracts: Vec<Contract> = serde_json::from_value(json!([{
+            "consumer": { "name": "ms.billing" },
+            "provider": { "name": "ms.order" },
+            "interactions": [{
+                "description": "an order",
+                "request": { "method": "get", "path": "/api/v1/orders/1" },
+                "response": { "status": 200 }
+            }]
+        }]))
+        .unwrap();
+        let normalizer = Normalizer::default().with_provider("ms.order", spec.routes());
+        contracts
+            .iter_mut()
+            .for_each(|contract| normalizer.normalize_contract(contract));
+        let inventory = Inventory::from_contracts(&contracts);
+
+        let comparison = Comparison::new("ms.order", &spec, &inventory.providers[0].endpoints);
+
+        assert_eq!(
+            render(&[comparison]),
+            "ms.order\n\
+             \x20 every operation is requested, every request is documented\n"
+        );
+        let swagger: OpenApi =
+            serde_yaml::from_str("swagger: '2.0'\nbasePath: /api/v2\npaths: {}").unwrap();
+        assert_eq!(swagger.prefix(), "/api/v2");
+    }
+}
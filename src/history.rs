@@ -0,0 +1,230 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeSet, fmt::Write};
+
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use futures::{stream, StreamExt, TryStreamExt};
+use log::warn;
+use pact_broker_api::client::{self, BrokerClient, MAX_CONCURRENT_REQUESTS};
+use pact_broker_models::contract::{Contract, Interaction};
+
+use crate::{cli::HistoryArgs, Settings};
+
+/// A version of the pact, with the interactions it defines.
+#[derive(Debug)]
+struct Version {
+    number: String,
+    created_at: Option<String>,
+    interactions: BTreeSet<String>,
+}
+
+impl From<&Contract> for Version {
+    fn from(contract: &Contract) -> Self {
+        let number = contract
+            .link("pb:consumer-version")
+            .and_then(|link| link.name.clone())
+            .unwrap_or_else(|| "?".to_owned());
+        let mut interactions: BTreeSet<String> = contract
+            .interactions
+            .iter()
+            .flatten()
+            .map(signature)
+            .collect();
+        interactions.extend(
+            contract
+                .messages
+                .iter()
+                .flatten()
+                .map(|message| message.description.clone().unwrap_or_default()),
+        );
+        Self {
+            number,
+            created_at: contract.created_at.clone(),
+            interactions,
+        }
+    }
+}
+
+/// Identify an interaction by its request, if any, and its description.
+fn signature(interaction: &Interaction) -> String {
+    let description = interaction.description().unwrap_or_default();
+    match interaction.as_http() {
+        Some(http) => format!(
+            "{} {}: {}",
+            http.request.method.to_uppercase(),
+            http.request.path,
+            description
+        ),
+        None => description.to_owned(),
+    }
+}
+
+/// Print the versions of the pact between `args.consumer` and `args.provider`,
+/// and the interactions added and removed by each version.
+pub async fn report(
+    settings: &Settings,
+    clients: &[BrokerClient],
+    args: &HistoryArgs,
+) -> Result<()> {
+    let mut found = false;
+    for (source, api) in settings.sources.iter().zip(clients) {
+        let latest = match api
+            .pacts()
+            .contract(&args.provider, &args.consumer, None)
+            .await
+        {
+            Ok(latest) => latest,
+            Err(e @ client::Error::NotFound { .. }) => {
+                warn!("No pact found in {}: {}", source.name, e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        found = true;
+
+        let versions = api.pacts().versions(&latest).await?;
+        // a missing version would show false changes between the others
+        let contracts: Vec<Contract> = stream::iter(versions.versions())
+            .map(|link| api.follow(link))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        let mut versions: Vec<Version> = contracts.iter().map(Version::from).collect();
+        sort_by_date(&mut versions);
+
+        println!(
+            "History of the pact between {} and {} ({})",
+            args.consumer, args.provider, source.name
+        );
+        print!("{}", render(&versions));
+    }
+    if !found {
+        bail!(
+            "No pact between {} and {} in the brokers",
+            args.consumer,
+            args.provider
+        );
+    }
+    Ok(())
+}
+
+/// Sort the versions by creation date, the oldest first,
+/// or keep the order of the broker when a date is missing or invalid.
+fn sort_by_date(versions: &mut [Version]) {
+    let date = |version: &Version| {
+        version
+            .created_at
+            .as_deref()
+            .and_then(|created_at| DateTime::parse_from_rfc3339(created_at).ok())
+    };
+    if versions.iter().all(|version| date(version).is_some()) {
+        versions.sort_by_cached_key(date);
+    }
+}
+
+/// List the versions, the oldest first, with the interactions added and removed by each of them.
+fn render(versions: &[Version]) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&Version> = None;
+    for version in versions {
+        let _ = writeln!(
+            out,
+            "{}  {}  {} interaction(s)",
+            version.number,
+            version.created_at.as_deref().unwrap_or("-"),
+            version.interactions.len()
+        );
+        if let Some(previous) = previous {
+            version
+                .interactions
+                .difference(&previous.interactions)
+                .for_each(|added| {
+                    let _ = writeln!(out, "  + {added}");
+                });
+            previous
+                .interactions
+                .difference(&version.interactions)
+                .for_each(|removed| {
+                    let _ = writeln!(out, "  - {removed}");
+                });
+        }
+        previous = Some(version);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use super::{render, sort_by_date, Version};
+
+    #[test]
+    fn should_render_added_and_removed_interactions() {
+        let contract = |version: &str, created_at: &str, paths: &[&str]| -> Contract {
+            let interactions: Vec<_> = paths
+                .iter()
+                .map(|path| {
+                    json!({
+                        "description": "a request",
+                        "request": { "method": "get", "path": path },
+                        "response": { "status": 200 }
+                    })
+                })
+                .collect();
+            serde_json::from_value(json!({
+                "consumer": { "name": "ms.billing" },
+                "provider": { "name": "ms.order" },
+                "interactions": interactions,
+                "createdAt": created_at,
+                "_links": { "pb:consumer-version": { "name": version, "href": "http://broker" } }
+            }))
+            .unwrap()
+        };
+        let versions: Vec<Version> = [
+            contract("1.0.0", "2023-01-09", &["/orders", "/orders/1"]),
+            contract("1.1.0", "2023-01-10", &["/orders", "/invoices"]),
+        ]
+        .iter()
+        .map(Version::from)
+        .collect();
+
+        assert_eq!(
+            render(&versions),
+            "1.0.0  2023-01-09  2 interaction(s)\n\
+             1.1.0  2023-01-10  2 interaction(s)\n\
+             \x20 + GET /invoices: a request\n\
+             \x20 - GET /orders/1: a request\n"
+        );
+    }
+
+    #[test]
+    fn should_sort_the_versions_by_date() {
+        let version = |number: &str, created_at: Option<&str>| Version {
+            number: number.to_owned(),
+            created_at: created_at.map(str::to_owned),
+            interactions: Default::default(),
+        };
+        let numbers = |versions: &[Version]| -> Vec<String> {
+            versions.iter().map(|v| v.number.clone()).collect()
+        };
+
+        let mut versions = vec![
+            version("1.1.0", Some("2023-01-10T09:00:00Z")),
+            version("1.0.0", Some("2023-01-10T10:00:00+02:00")),
+        ];
+        sort_by_date(&mut versions);
+        assert_eq!(numbers(&versions), ["1.0.0", "1.1.0"]);
+
+        let mut versions = vec![
+            version("1.0.0", Some("2023-01-10T09:00:00Z")),
+            version("1.1.0", None),
+            version("0.9.0", Some("2023-01-09T09:00:00Z")),
+        ];
+        sort_by_date(&mut versions);
+        assert_eq!(numbers(&versions), ["1.0.0", "1.1.0", "0.9.0"]);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use anyhow::{anyhow, Result};
+use pact_broker_api::client::BrokerClient;
+use pact_broker_models::contract::{Contract, HttpInteraction};
+use serde::Serialize;
+
+use crate::{fetch_sources, reporter, InventoryArgs, InventoryFormat, Settings};
+
+/// Endpoints of every provider, as consumed by the contracts.
+#[derive(Debug, Serialize, Default)]
+pub(crate) struct Inventory {
+    pub(crate) providers: Vec<Provider>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Provider {
+    pub(crate) name: String,
+    pub(crate) endpoints: Vec<Endpoint>,
+}
+
+/// A `METHOD path` of a provider, with what its consumers expect from it.
+#[derive(Debug, Serialize, PartialEq)]
+pub(crate) struct Endpoint {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) consumers: BTreeSet<String>,
+    /// response statuses expected by the consumers
+    statuses: BTreeSet<i16>,
+    provider_states: BTreeSet<String>,
+}
+
+impl Inventory {
+    /// Gather the HTTP interactions of `contracts` by provider and endpoint.
+    pub(crate) fn from_contracts<'a, I>(contracts: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Contract>,
+    {
+        let mut providers = BTreeMap::<&str, BTreeMap<(String, &str), Endpoint>>::new();
+        for contract in contracts {
+            let endpoints = providers.entry(&contract.provider.name).or_default();
+            for interaction in contract
+                .interactions
+                .iter()
+                .flatten()
+                .filter_map(|interaction| interaction.as_http())
+            {
+                let method = interaction.request.method.to_uppercase();
+                let endpoint = endpoints
+                    .entry((method.clone(), interaction.request.route()))
+                    .or_insert_with(|| Endpoint {
+                        method,
+                        path: interaction.request.route().to_owned(),
+                        consumers: BTreeSet::new(),
+                        statuses: BTreeSet::new(),
+                        provider_states: BTreeSet::new(),
+                    });
+                endpoint.consumers.insert(contract.consumer.name.clone());
+                endpoint.statuses.insert(interaction.response.status);
+                endpoint
+                    .provider_states
+                    .extend(provider_states(interaction));
+            }
+        }
+        Self {
+            providers: providers
+                .into_iter()
+                .filter(|(_, endpoints)| !endpoints.is_empty())
+                .map(|(name, endpoints)| Provider {
+                    name: name.to_owned(),
+                    endpoints: endpoints.into_values().collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Names of the provider states of the interaction, of pact specification v2 or v3.
+pub(crate) fn provider_states(interaction: &HttpInteraction) -> impl Iterator<Item = String> + '_ {
+    interaction.provider_state.iter().cloned().chain(
+        interaction
+            .provider_states
+            .iter()
+            .flatten()
+            .map(|state| state.name.clone()),
+    )
+}
+
+/// Print the endpoints of every provider relied upon by the consumers,
+/// or write them as an HTML page.
+pub async fn report(
+    settings: &Settings,
+    clients: &[BrokerClient],
+    args: &InventoryArgs,
+) -> Result<()> {
+    let sources = fetch_sources(settings, clients).await?;
+    let inventory = Inventory::from_contracts(sources.iter().flat_map(|source| &source.contracts));
+
+    match args.format {
+        InventoryFormat::Markdown => print!("{}", markdown(&inventory)),
+        InventoryFormat::Json => println!("{}", serde_json::to_string_pretty(&inventory)?),
+        InventoryFormat::Html => {
+            reporter::write_inventory(&settings.output, &inventory)
+                .map_err(|e| anyhow!("Could not generate the endpoints page: {}", e))?;
+            println!(
+                "Endpoints written to {}",
+                settings.output.join(reporter::INVENTORY_FILE).display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A table of the endpoints for each provider.
+fn markdown(inventory: &Inventory) -> String {
+    let join = |items: Vec<String>| items.join(", ").replace('|', "\\|");
+    let mut out = String::from("# Endpoints\n");
+    for provider in &inventory.providers {
+        let _ = writeln!(out, "\n## {}\n", provider.name);
+        out.push_str("| Endpoint | Consumers | Statuses | Provider states |\n");
+        out.push_str("| --- | --- | --- | --- |\n");
+        for endpoint in &provider.endpoints {
+            let _ = writeln!(
+                out,
+                "| `{} {}` | {} | {} | {} |",
+                endpoint.method,
+                endpoint.path,
+                join(endpoint.consumers.iter().cloned().collect()),
+                join(endpoint.statuses.iter().map(i16::to_string).collect()),
+                join(endpoint.provider_states.iter().cloned().collect()),
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use super::{markdown, Inventory};
+
+    #[test]
+    fn should_gather_the_endpoints_by_provider() {
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            {
+                "consumer": { "name": "ms.billing" },
+                "provider": { "name": "ms.order" },
+                "interactions": [
+                    {
+                        "description": "an order",
+                        "providerState": "an order exists",
+                        "request": { "method": "get", "path": "/orders/1" },
+                        "response": { "status": 200 }
+                    },
+                    {
+                        "description": "a missing order",
+                        "request": { "method": "get", "path": "/orders/1" },
+                        "response": { "status": 404 }
+                    }
+                ]
+            },
+            {
+                "consumer": { "name": "ms.shipping" },
+                "provider": { "name": "ms.order" },
+                "interactions": [
+                    {
+                        "description": "an order",
+                        "providerStates": [{ "name": "an order | shipped" }],
+                        "request": { "method": "GET", "path": "/orders/1" },
+                        "response": { "status": 200 }
+                    },
+                    {
+                        "description": "a new order",
+                        "request": { "method": "post", "path": "/orders" },
+                        "response": { "status": 201 }
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            markdown(&Inventory::from_contracts(&contracts)),
+            "# Endpoints\n\
+             \n\
+             ## ms.order\n\
+             \n\
+             | Endpoint | Consumers | Statuses | Provider states |\n\
+             | --- | --- | --- | --- |\n\
+             | `GET /orders/1` | ms.billing, ms.shipping | 200, 404 | an order exists, an order \\| shipped |\n\
+             | `POST /orders` | ms.shipping | 201 |  |\n"
+        );
+    }
+}
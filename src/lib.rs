@@ -1,68 +1,251 @@
 mod chart;
+mod check;
 mod cli;
+mod config;
+mod coverage;
+mod history;
+mod inventory;
+mod local;
+mod metrics;
+mod openapi;
+mod orphans;
 mod reporter;
+mod routes;
+mod server;
+mod stale;
 mod utils;
+mod watch;
+mod webhooks;
 
-use anyhow::Result;
-pub use cli::{Cli, GraphChoice};
-use log::{debug, info};
-use pact_broker_api::client::Builder;
-use pact_broker_models::contract::Contract;
+use anyhow::{bail, Result};
+pub use cli::{
+    CheckArgs, Cli, Command, CoverageArgs, GraphChoice, HistoryArgs, InventoryArgs,
+    InventoryFormat, MetricsArgs, MetricsColumn, MetricsFormat, Rule, ServeArgs,
+};
+pub use config::{Config, Credentials, OAuth2, Profile, Settings, Source};
+use futures::future::try_join_all;
+use log::{debug, error, info};
+use pact_broker_api::client::{BrokerClient, Builder, CommandToken, OAuth2ClientCredentials};
+use pact_broker_models::{contract::Contract, index::Capability};
 use reqwest::Url;
-use std::{path::Path, time::Duration};
 
 use crate::chart::dataset;
+use crate::utils::pattern;
+use crate::watch::PactWatcher;
 
 pub async fn run(args: Cli) -> Result<()> {
-    info!("Base URL: {}", args.url);
-    let output = Path::new(&args.output);
-    info!("Output: {}", output.display());
-    debug!("Exclude: {:?}", &args.exclude);
+    let settings = Settings::load(&args)?;
+    settings
+        .sources
+        .iter()
+        .for_each(|source| info!("Base URL: {} ({})", source.url, source.name));
+    settings
+        .pact_dirs
+        .iter()
+        .for_each(|dir| info!("Pact directory: {}", dir.display()));
+    info!("Output: {}", settings.output.display());
+    debug!("Exclude: {:?}", &settings.exclude);
 
-    let timeout = Duration::from_millis(args.timeout as u64);
+    let clients = settings
+        .sources
+        .iter()
+        .map(broker_client)
+        .collect::<Result<Vec<_>>>()?;
 
-    let mut api_builder = Builder::new()
-        .base_url(args.url)
-        .unwrap()
-        .with_timeout(timeout);
+    let watcher = match args.watch {
+        true if settings.pact_dirs.is_empty() => {
+            bail!("`--watch` requires a pact directory, use `--pact-dir`")
+        }
+        true => Some(PactWatcher::new(&settings.pact_dirs)?),
+        false => None,
+    };
+
+    match args.command {
+        Some(Command::Serve(serve)) => server::serve(settings, clients, serve, watcher).await,
+        Some(Command::Info) => print_info(&settings, &clients).await,
+        Some(Command::Webhooks) => webhooks::report(&settings, &clients).await,
+        Some(Command::History(history)) => history::report(&settings, &clients, &history).await,
+        Some(Command::Metrics(metrics)) => metrics::report(&settings, &clients, &metrics).await,
+        Some(Command::Endpoints(inventory)) => {
+            inventory::report(&settings, &clients, &inventory).await
+        }
+        Some(Command::Coverage(coverage)) => coverage::report(&settings, &clients, &coverage).await,
+        Some(Command::Stale) => stale::report(&settings, &clients).await,
+        Some(Command::Check(check)) => check::run(&settings, &clients, &check).await,
+        None => {
+            write_report(&settings, &clients).await?;
+            if let Some(mut watcher) = watcher {
+                println!("Watching the pact files, press Ctrl-C to stop");
+                loop {
+                    tokio::select! {
+                        _ = watcher.changed() => (),
+                        _ = tokio::signal::ctrl_c() => return Ok(()),
+                    }
+                    info!("Pact files changed, rendering the report");
+                    if let Err(e) = write_report(&settings, &clients).await {
+                        error!("Could not render the report: {:#}", e);
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print the capabilities of every broker of `settings`.
+async fn print_info(settings: &Settings, clients: &[BrokerClient]) -> Result<()> {
+    for (source, api) in settings.sources.iter().zip(clients) {
+        let index = api.index().await?;
+        println!("{} ({})", source.name, source.url);
+        for capability in Capability::ALL {
+            let mark = if index.supports(capability) { 'x' } else { ' ' };
+            println!("  [{mark}] {capability}");
+        }
+    }
+    Ok(())
+}
 
-    if let (Some(username), Some(password)) = (args.username, args.password) {
-        api_builder = api_builder.basic_auth(&username, &password);
+/// The contracts of a broker or of a directory of pact files.
+#[derive(Debug)]
+pub(crate) struct ContractSource {
+    pub(crate) name: String,
+    /// client of the broker, `None` for a directory of pact files
+    pub(crate) client: Option<BrokerClient>,
+    pub(crate) contracts: Vec<Contract>,
+}
+
+async fn write_report(settings: &Settings, clients: &[BrokerClient]) -> Result<()> {
+    let sources = fetch_sources(settings, clients).await?;
+    let mut graph = build_graph(settings, &sources);
+    // the verifications take a request per contract, only fetch them for the charts showing them,
+    // those which could not be fetched being shown as unknown
+    if matches!(
+        settings.graph,
+        GraphChoice::Edge | GraphChoice::Directed | GraphChoice::Matrix
+    ) {
+        graph = graph.with_verifications(&stale::statuses(&sources).await);
     }
+    let json_data = serde_json::to_string(&graph)?;
+    reporter::write_report(&settings.output, settings.graph, json_data)
+        .expect("Could not generate the report");
+    Ok(())
+}
 
-    if let Some(token) = args.token {
-        api_builder = api_builder.token(&token);
+/// Fetch the contracts of every broker of `settings` with its client,
+/// read the pact files of the local directories, and merge them into a single graph.
+async fn fetch_graph(settings: &Settings, clients: &[BrokerClient]) -> Result<dataset::Graph> {
+    let brokers = fetch_sources(settings, clients).await?;
+    Ok(build_graph(settings, &brokers))
+}
+
+/// Merge the contracts of every source into a single graph.
+fn build_graph(settings: &Settings, brokers: &[ContractSource]) -> dataset::Graph {
+    // tag nodes and links only when several sources are merged
+    let tagged = brokers.len() > 1;
+    dataset::Graph::from_brokers(brokers.iter().map(|source| {
+        (
+            tagged.then_some(source.name.as_str()),
+            source.contracts.as_slice(),
+        )
+    }))
+    .with_groups(&settings.groups)
+    .with_staleness(settings.stale_after, chrono::Utc::now())
+    .with_layers()
+    .with_flows()
+}
+
+/// Fetch the contracts of every broker of `settings` with its client and read the pact files
+/// of the local directories, by source name, without the contracts of the excluded services.
+/// The templates of the paths of the requests are set when `--route` or `--openapi` is given.
+async fn fetch_sources(
+    settings: &Settings,
+    clients: &[BrokerClient],
+) -> Result<Vec<ContractSource>> {
+    let mut brokers = try_join_all(settings.sources.iter().zip(clients).map(
+        |(source, api)| async move {
+            anyhow::Ok(ContractSource {
+                name: source.name.clone(),
+                client: Some(api.clone()),
+                contracts: fetch_contracts(api).await?,
+            })
+        },
+    ))
+    .await?;
+    for dir in &settings.pact_dirs {
+        brokers.push(ContractSource {
+            name: dir.display().to_string(),
+            client: None,
+            contracts: local::read_contracts(dir)?,
+        });
     }
 
-    let api = api_builder.build().unwrap();
+    let exclude = &settings.exclude;
+    let normalizer = routes::Normalizer::load(settings)?;
+    brokers.iter_mut().for_each(|source| {
+        let data = &mut source.contracts;
+        data.retain(|contract| {
+            !pattern::matches_any(exclude, &contract.consumer.name)
+                && !pattern::matches_any(exclude, &contract.provider.name)
+        });
+        if let Some(normalizer) = &normalizer {
+            data.iter_mut()
+                .for_each(|contract| normalizer.normalize_contract(contract));
+        }
+    });
+    Ok(brokers)
+}
+
+/// Fetch the latest version of every contract of the broker.
+pub async fn fetch_contracts(api: &BrokerClient) -> Result<Vec<Contract>> {
     let urls: Vec<Url> = match api.pacts().latest().await {
         Ok(pacts) => pacts
             .pacts
             .iter()
-            .filter_map(|pact| match pact.links.links_self.first() {
-                Some(link) => match Url::parse(link.href.as_str()) {
-                    Ok(link) => Some(link),
-                    Err(_) => None,
-                },
+            .filter_map(|pact| match pact.link() {
+                Some(link) => Url::parse(link.href.as_str()).ok(),
                 None => None,
             })
             .collect(),
         Err(e) => {
-            eprintln!("Failed to fetch latests pacts:");
+            eprintln!("Failed to fetch latests pacts from {}:", api.base_url);
             return Err(e.into());
         }
     };
 
-    let data = match api.batch_get::<Contract>(urls, None).await {
-        Ok(data) => data,
+    match api.batch_get::<Contract>(urls, None).await {
+        Ok(data) => Ok(data),
         Err(e) => {
             eprintln!("Failed to fetch iterate over contracts:");
-            return Err(e.into());
+            Err(e.into())
+        }
+    }
+}
+
+/// Build the `BrokerClient` of the broker `source`.
+pub fn broker_client(source: &Source) -> Result<BrokerClient> {
+    let mut api_builder = Builder::new()
+        .base_url(source.url.as_str())?
+        .with_timeout(source.timeout);
+
+    api_builder = match &source.credentials {
+        None => api_builder,
+        Some(Credentials::Basic { username, password }) => {
+            api_builder.basic_auth(username, password)
+        }
+        Some(Credentials::Token(token)) => api_builder.token(token),
+        Some(Credentials::TokenCommand(command)) => api_builder.auth(CommandToken::shell(command)),
+        Some(Credentials::OAuth2(oauth2)) => {
+            let mut auth = OAuth2ClientCredentials::new(
+                Url::parse(&oauth2.token_url)?,
+                &oauth2.client_id,
+                &oauth2.client_secret,
+            );
+            if let Some(scope) = &oauth2.scope {
+                auth = auth.with_scope(scope);
+            }
+            api_builder.auth(auth)
         }
     };
 
-    let graph = dataset::Graph::from(&data);
-    let json_data = serde_json::to_string(&graph)?;
-    reporter::write_report(output, args.graph, json_data).expect("Could not generate the report");
-    Ok(())
+    Ok(api_builder.build()?)
 }
@@ -0,0 +1,73 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use pact_broker_models::contract::Contract;
+
+/// Read every pact file (`*.json`) of `dir` and of its sub directories.
+/// Files which are not pacts are skipped.
+pub fn read_contracts(dir: &Path) -> Result<Vec<Contract>> {
+    let mut contracts = vec![];
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Could not read directory {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            contracts.extend(read_contracts(&path)?);
+        } else if is_pact_file(&path) {
+            debug!("Reading {}", path.display());
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Could not read {}", path.display()))?;
+            match serde_json::from_str::<Contract>(&content) {
+                Ok(contract) => contracts.push(contract),
+                Err(e) => warn!("Skipping {}, not a pact file: {}", path.display(), e),
+            }
+        }
+    }
+    Ok(contracts)
+}
+
+/// Whether `path` looks like a pact file.
+pub fn is_pact_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::read_contracts;
+
+    #[test]
+    fn should_read_the_pact_files_of_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let pact = |consumer: &str| {
+            format!(
+                r#"{{ "consumer": {{ "name": "{consumer}" }}, "provider": {{ "name": "ms.order" }} }}"#
+            )
+        };
+        fs::write(dir.path().join("billing-order.json"), pact("ms.billing")).unwrap();
+        fs::create_dir(dir.path().join("shipping")).unwrap();
+        fs::write(
+            dir.path().join("shipping/shipping-order.json"),
+            pact("ms.shipping"),
+        )
+        .unwrap();
+        // skipped: not a pact file, and a malformed pact
+        fs::write(dir.path().join("README.md"), "# pacts").unwrap();
+        fs::write(dir.path().join("broken.json"), r#"{ "consumer": "#).unwrap();
+
+        let mut consumers: Vec<String> = read_contracts(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|contract| contract.consumer.name)
+            .collect();
+        consumers.sort();
+
+        assert_eq!(consumers, vec!["ms.billing", "ms.shipping"]);
+        assert!(read_contracts(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{cmp::Ordering, fmt::Write};
+
+use anyhow::{anyhow, Result};
+use pact_broker_api::client::BrokerClient;
+
+use crate::{
+    chart::metrics::NodeMetrics, fetch_graph, reporter, MetricsArgs, MetricsColumn, MetricsFormat,
+    Settings,
+};
+
+const HEADERS: [&str; 9] = [
+    "service",
+    "group",
+    "fan_in",
+    "fan_out",
+    "consumed",
+    "provided",
+    "betweenness",
+    "pagerank",
+    "longest_chain",
+];
+
+/// Print the metrics of every service, or write them as an HTML page.
+pub async fn report(
+    settings: &Settings,
+    clients: &[BrokerClient],
+    args: &MetricsArgs,
+) -> Result<()> {
+    let graph = fetch_graph(settings, clients).await?;
+    let mut metrics = graph.metrics();
+    sort(&mut metrics, args.sort);
+
+    match args.format {
+        MetricsFormat::Console => print!("{}", console(&metrics)),
+        MetricsFormat::Csv => print!("{}", csv(&metrics)),
+        MetricsFormat::Json => println!("{}", serde_json::to_string_pretty(&metrics)?),
+        MetricsFormat::Html => {
+            reporter::write_metrics(&settings.output, serde_json::to_string(&metrics)?)
+                .map_err(|e| anyhow!("Could not generate the metrics page: {}", e))?;
+            println!(
+                "Metrics written to {}",
+                settings.output.join(reporter::METRICS_FILE).display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Sort by `column`, names in ascending order and numbers in descending order.
+fn sort(metrics: &mut [NodeMetrics], column: MetricsColumn) {
+    let key = |m: &NodeMetrics| -> f64 {
+        match column {
+            MetricsColumn::Name => 0.0,
+            MetricsColumn::FanIn => m.fan_in as f64,
+            MetricsColumn::FanOut => m.fan_out as f64,
+            MetricsColumn::Consumed => m.consumed as f64,
+            MetricsColumn::Provided => m.provided as f64,
+            MetricsColumn::Betweenness => m.betweenness,
+            MetricsColumn::Pagerank => m.pagerank,
+            MetricsColumn::LongestChain => m.longest_chain as f64,
+        }
+    };
+    metrics.sort_by(|a, b| {
+        key(b)
+            .partial_cmp(&key(a))
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+fn row(m: &NodeMetrics) -> [String; 9] {
+    [
+        m.name.clone(),
+        m.group.clone().unwrap_or_default(),
+        m.fan_in.to_string(),
+        m.fan_out.to_string(),
+        m.consumed.to_string(),
+        m.provided.to_string(),
+        format!("{:.2}", m.betweenness),
+        format!("{:.4}", m.pagerank),
+        m.longest_chain.to_string(),
+    ]
+}
+
+/// Table aligned on the widest cell of each column.
+fn console(metrics: &[NodeMetrics]) -> String {
+    let rows: Vec<[String; 9]> = metrics.iter().map(row).collect();
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([HEADERS[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    let headers = HEADERS.map(str::to_owned);
+    for row in std::iter::once(&headers).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                // names on the left, numbers on the right
+                0 | 1 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect();
+        let _ = writeln!(out, "{}", line.join("  ").trim_end());
+    }
+    out
+}
+
+fn csv(metrics: &[NodeMetrics]) -> String {
+    let escape = |cell: &str| {
+        if cell.contains([',', '"', '\n']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_owned()
+        }
+    };
+    let mut out = HEADERS.join(",");
+    out.push('\n');
+    for m in metrics {
+        let cells: Vec<String> = row(m).iter().map(|cell| escape(cell)).collect();
+        let _ = writeln!(out, "{}", cells.join(","));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{chart::metrics::NodeMetrics, MetricsColumn};
+
+    use super::{console, csv, sort};
+
+    fn metrics(name: &str, fan_in: usize, pagerank: f64) -> NodeMetrics {
+        NodeMetrics {
+            name: name.to_owned(),
+            group: None,
+            fan_in,
+            fan_out: 0,
+            consumed: 0,
+            provided: fan_in,
+            betweenness: 0.0,
+            pagerank,
+            longest_chain: 0,
+        }
+    }
+
+    #[test]
+    fn should_sort_and_format_the_table() {
+        let mut table = vec![
+            metrics("ms.billing", 1, 0.2),
+            metrics("ms.order, v2", 3, 0.5),
+            metrics("ms.stock", 1, 0.3),
+        ];
+
+        sort(&mut table, MetricsColumn::FanIn);
+        let names: Vec<&str> = table.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["ms.order, v2", "ms.billing", "ms.stock"]);
+
+        assert_eq!(
+            csv(&table).lines().nth(1),
+            Some("\"ms.order, v2\",,3,0,0,3,0.00,0.5000,0")
+        );
+        assert_eq!(
+            console(&table).lines().nth(1),
+            Some("ms.order, v2              3        0         0         3         0.00    0.5000              0")
+        );
+    }
+}
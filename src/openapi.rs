@@ -0,0 +1,111 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// The parts of an OpenAPI document describing the routes of a provider.
+#[derive(Debug, Deserialize)]
+pub struct OpenApi {
+    /// Operations by path template, e.g. `/orders/{orderId}`
+    #[serde(default)]
+    pub paths: BTreeMap<String, PathItem>,
+    /// Servers of the API, whose URL path prefixes the paths (OpenAPI 3)
+    #[serde(default)]
+    pub servers: Vec<Server>,
+    /// Prefix of the paths (Swagger 2)
+    #[serde(rename = "basePath")]
+    pub base_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Server {
+    pub url: String,
+}
+
+/// Operations of a path, by HTTP method.
+#[derive(Debug, Default, Deserialize)]
+pub struct PathItem {
+    pub get: Option<Operation>,
+    pub put: Option<Operation>,
+    pub post: Option<Operation>,
+    pub delete: Option<Operation>,
+    pub options: Option<Operation>,
+    pub head: Option<Operation>,
+    pub patch: Option<Operation>,
+    pub trace: Option<Operation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Operation {
+    #[serde(rename = "operationId")]
+    pub operation_id: Option<String>,
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+impl OpenApi {
+    /// Read an OpenAPI document, in YAML or in JSON.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read OpenAPI document {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid OpenAPI document {}", path.display()))
+    }
+
+    /// Path the API is served under, e.g. `/api/v1`, taken from the URL of the first server
+    /// or from the `basePath`, empty when served at the root.
+    pub fn prefix(&self) -> &str {
+        let prefix = match (self.servers.first(), &self.base_path) {
+            (Some(server), _) => match server.url.split_once("://") {
+                // the path following the host of an absolute URL
+                Some((_, rest)) => rest.find('/').map_or("", |start| &rest[start..]),
+                None => server.url.as_str(),
+            },
+            (None, Some(base_path)) => base_path.as_str(),
+            (None, None) => "",
+        };
+        prefix.trim_end_matches('/')
+    }
+
+    /// Path templates of the requests, the paths of the document prefixed by [`OpenApi::prefix`].
+    pub fn routes(&self) -> impl Iterator<Item = String> + '_ {
+        self.paths
+            .keys()
+            .map(|path| format!("{}{}", self.prefix(), path))
+    }
+
+    /// Every operation of the document, with its method in upper case and its prefixed path.
+    pub fn operations(&self) -> Vec<(&'static str, String, &Operation)> {
+        self.paths
+            .iter()
+            .flat_map(|(path, item)| {
+                item.operations()
+                    .into_iter()
+                    .map(move |(method, operation)| {
+                        (method, format!("{}{}", self.prefix(), path), operation)
+                    })
+            })
+            .collect()
+    }
+}
+
+impl PathItem {
+    fn operations(&self) -> Vec<(&'static str, &Operation)> {
+        [
+            ("GET", &self.get),
+            ("PUT", &self.put),
+            ("POST", &self.post),
+            ("DELETE", &self.delete),
+            ("OPTIONS", &self.options),
+            ("HEAD", &self.head),
+            ("PATCH", &self.patch),
+            ("TRACE", &self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_ref().map(|operation| (method, operation)))
+        .collect()
+    }
+}
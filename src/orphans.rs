@@ -0,0 +1,239 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use futures::future::try_join_all;
+use pact_broker_api::client::BrokerClient;
+use pact_broker_models::contract::Contract;
+use serde::Serialize;
+
+use crate::{
+    fetch_sources,
+    stale::{self, PactStatus, VerificationStatus},
+    utils::pattern,
+    ContractSource, Settings,
+};
+
+/// Services and contracts out of the dependency graph, signs of a poor broker hygiene.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct Orphans {
+    /// pacticipants of the brokers taking part in no contract
+    pub without_contract: Vec<String>,
+    /// providers which verified none of their pacts
+    pub unverified_providers: Vec<String>,
+    /// pacts whose provider is not a pacticipant of the broker any more, as `(consumer, provider)`
+    pub missing_providers: Vec<(String, String)>,
+    /// groups of services linked to none of the services of the largest group
+    pub disconnected: Vec<Vec<String>>,
+}
+
+impl Orphans {
+    /// Find the orphans of the contracts of each source, `pacticipants` holding the names of the
+    /// pacticipants of each broker, the brokers coming first in `sources`.
+    /// The pacticipants matching a pattern of `exclude` are left out, as their contracts are.
+    pub fn new(
+        sources: &[ContractSource],
+        pacticipants: &[Vec<String>],
+        statuses: &[PactStatus],
+        exclude: &[String],
+    ) -> Self {
+        let mut orphans = Self::default();
+
+        let mut involved = BTreeSet::new();
+        for (i, source) in sources.iter().enumerate() {
+            let known = pacticipants.get(i);
+            for contract in &source.contracts {
+                involved.insert(contract.consumer.name.as_str());
+                involved.insert(contract.provider.name.as_str());
+                if known.is_some_and(|known| !known.contains(&contract.provider.name)) {
+                    orphans.missing_providers.push((
+                        contract.consumer.name.clone(),
+                        contract.provider.name.clone(),
+                    ));
+                }
+            }
+        }
+        orphans.without_contract = pacticipants
+            .iter()
+            .flatten()
+            .filter(|name| {
+                !involved.contains(name.as_str()) && !pattern::matches_any(exclude, name)
+            })
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        // whether each provider verified any of its pacts, when known
+        let mut verified = BTreeMap::<&str, bool>::new();
+        for status in statuses {
+            if let Some(verification) = status.verification {
+                *verified.entry(&status.provider).or_default() |=
+                    verification != VerificationStatus::Unverified;
+            }
+        }
+        orphans.unverified_providers = verified
+            .into_iter()
+            .filter(|(_, verified)| !verified)
+            .map(|(provider, _)| provider.to_owned())
+            .collect();
+
+        let mut components = components(sources.iter().flat_map(|source| &source.contracts));
+        if !components.is_empty() {
+            components.remove(0);
+        }
+        orphans.disconnected = components;
+        orphans.missing_providers.sort();
+        orphans.missing_providers.dedup();
+        orphans
+    }
+}
+
+/// Groups of services linked by the contracts, whatever their direction, the largest first.
+fn components<'a, I>(contracts: I) -> Vec<Vec<String>>
+where
+    I: IntoIterator<Item = &'a Contract>,
+{
+    // union-find of the services, by name
+    fn root<'a>(parent: &mut BTreeMap<&'a str, &'a str>, name: &'a str) -> &'a str {
+        let mut current = name;
+        while let Some(&next) = parent.get(current) {
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        parent.insert(name, current);
+        current
+    }
+
+    let mut parent = BTreeMap::<&str, &str>::new();
+    for contract in contracts {
+        let consumer = contract.consumer.name.as_str();
+        let provider = contract.provider.name.as_str();
+        parent.entry(consumer).or_insert(consumer);
+        parent.entry(provider).or_insert(provider);
+        let (a, b) = (root(&mut parent, consumer), root(&mut parent, provider));
+        if a != b {
+            parent.insert(a.max(b), a.min(b));
+        }
+    }
+
+    let names: Vec<&str> = parent.keys().copied().collect();
+    let mut groups = BTreeMap::<&str, Vec<String>>::new();
+    for name in names {
+        let root = root(&mut parent, name);
+        groups.entry(root).or_default().push(name.to_owned());
+    }
+    let mut groups: Vec<Vec<String>> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    groups
+}
+
+/// Fetch the contracts and the pacticipants of every broker, and find the orphans.
+pub async fn fetch(settings: &Settings, clients: &[BrokerClient]) -> Result<Orphans> {
+    let sources = fetch_sources(settings, clients).await?;
+    let statuses = stale::statuses(&sources).await;
+    let pacticipants = fetch_pacticipants(clients).await?;
+    Ok(Orphans::new(
+        &sources,
+        &pacticipants,
+        &statuses,
+        &settings.exclude,
+    ))
+}
+
+/// The names of the pacticipants of each broker.
+pub async fn fetch_pacticipants(clients: &[BrokerClient]) -> Result<Vec<Vec<String>>> {
+    let pacticipants = try_join_all(clients.iter().map(|api| async move {
+        let list = api.pacticipants().list().await?;
+        anyhow::Ok(
+            list.pacticipants()
+                .iter()
+                .map(|pacticipant| pacticipant.name.clone())
+                .collect(),
+        )
+    }))
+    .await?;
+    Ok(pacticipants)
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::contract::Contract;
+    use serde_json::json;
+
+    use super::Orphans;
+    use crate::{
+        stale::{PactStatus, VerificationStatus},
+        ContractSource,
+    };
+
+    #[test]
+    fn should_find_orphans() {
+        let pact = |consumer: &str, provider: &str| json!({ "consumer": { "name": consumer }, "provider": { "name": provider } });
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            pact("front", "gateway"),
+            pact("gateway", "order"),
+            pact("gateway", "legacy"),
+            pact("batch", "stock"),
+        ]))
+        .unwrap();
+        let sources = vec![ContractSource {
+            name: "broker".to_owned(),
+            client: None,
+            contracts,
+        }];
+        let pacticipants = vec![["front", "gateway", "order", "batch", "stock", "unused"]
+            .map(str::to_owned)
+            .to_vec()];
+        let status = |provider: &str, verification| PactStatus {
+            broker: None,
+            consumer: "gateway".to_owned(),
+            provider: provider.to_owned(),
+            created_at: None,
+            verification,
+        };
+        let statuses = [
+            status("order", Some(VerificationStatus::Unverified)),
+            status("order", Some(VerificationStatus::Failure)),
+            status("stock", Some(VerificationStatus::Unverified)),
+            status("legacy", None),
+        ];
+
+        let orphans = Orphans::new(&sources, &pacticipants, &statuses, &[]);
+
+        assert_eq!(
+            orphans,
+            Orphans {
+                without_contract: vec!["unused".to_owned()],
+                unverified_providers: vec!["stock".to_owned()],
+                missing_providers: vec![("gateway".to_owned(), "legacy".to_owned())],
+                disconnected: vec![vec!["batch".to_owned(), "stock".to_owned()]],
+            }
+        );
+    }
+
+    #[test]
+    fn should_leave_out_the_excluded_pacticipants() {
+        // the contracts of the excluded services are already filtered out
+        let contracts: Vec<Contract> = serde_json::from_value(json!([
+            { "consumer": { "name": "front" }, "provider": { "name": "gateway" } },
+        ]))
+        .unwrap();
+        let sources = vec![ContractSource {
+            name: "broker".to_owned(),
+            client: None,
+            contracts,
+        }];
+        let pacticipants = vec![["front", "gateway", "legacy.billing", "unused"]
+            .map(str::to_owned)
+            .to_vec()];
+
+        let orphans = Orphans::new(&sources, &pacticipants, &[], &["legacy.*".to_owned()]);
+
+        assert_eq!(orphans.without_contract, vec!["unused".to_owned()]);
+    }
+}
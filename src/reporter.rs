@@ -16,9 +16,13 @@ use serde::Serialize;
 #[folder = "templates/"]
 struct Templates;
 
+pub const METRICS_FILE: &str = "metrics.html";
+pub const INVENTORY_FILE: &str = "endpoints.html";
+
 #[derive(Debug, Serialize)]
 struct Data {
     json_data: String,
+    live_reload: bool,
 }
 
 pub fn write_report(
@@ -30,21 +34,87 @@ pub fn write_report(
         stdFs::create_dir_all(output)?;
     }
 
-    let mut hbs = Handlebars::new();
-    hbs.register_embed_templates::<Templates>()?;
+    let html_contents = render(graph, data, false)?;
+    fs::write(output.join(graph.file_name()), html_contents)?;
 
-    let template = match graph {
-        GraphChoice::Edge => "edge-bundling.hbs",
-        GraphChoice::Directed => "force-directed.hbs",
+    Ok(())
+}
+
+/// Render the `graph` chart of the JSON `data` as an HTML page.
+/// With `live_reload`, the page reloads itself when the served graph changed.
+pub fn render(
+    graph: GraphChoice,
+    data: String,
+    live_reload: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let hbs = handlebars()?;
+    let data = Data {
+        json_data: data,
+        live_reload,
     };
+    Ok(hbs.render(graph.template(), &data)?)
+}
+
+/// Write the metrics table of the JSON `data` in the `output` directory.
+pub fn write_metrics(output: &Path, data: String) -> Result<(), Box<dyn std::error::Error>> {
+    if !output.exists() {
+        stdFs::create_dir_all(output)?;
+    }
+
+    let hbs = handlebars()?;
+    let data = Data {
+        json_data: data,
+        live_reload: false,
+    };
+    fs::write(output.join(METRICS_FILE), hbs.render("metrics.hbs", &data)?)?;
+
+    Ok(())
+}
 
-    let data = Data { json_data: data };
+/// Write the endpoints of every provider in the `output` directory.
+pub fn write_inventory<S: Serialize>(
+    output: &Path,
+    data: &S,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !output.exists() {
+        stdFs::create_dir_all(output)?;
+    }
 
-    let html_contents = hbs.render(template, &data)?;
+    let hbs = handlebars()?;
     fs::write(
-        output.join(template.replace(".hbs", ".html")),
-        html_contents,
+        output.join(INVENTORY_FILE),
+        hbs.render("endpoints.hbs", data)?,
     )?;
 
     Ok(())
 }
+
+/// Render the page linking every chart.
+pub fn render_index<S: Serialize>(data: &S) -> Result<String, Box<dyn std::error::Error>> {
+    let hbs = handlebars()?;
+    Ok(hbs.render("index.hbs", data)?)
+}
+
+fn handlebars() -> Result<Handlebars<'static>, Box<dyn std::error::Error>> {
+    let mut hbs = Handlebars::new();
+    hbs.register_embed_templates::<Templates>()?;
+    Ok(hbs)
+}
+
+impl GraphChoice {
+    /// Name of the Handlebars template of the chart.
+    pub fn template(&self) -> &'static str {
+        match self {
+            GraphChoice::Edge => "edge-bundling.hbs",
+            GraphChoice::Directed => "force-directed.hbs",
+            GraphChoice::Layered => "layered.hbs",
+            GraphChoice::Matrix => "matrix.hbs",
+            GraphChoice::Sankey => "sankey.hbs",
+        }
+    }
+
+    /// Name of the HTML file of the chart.
+    pub fn file_name(&self) -> String {
+        self.template().replace(".hbs", ".html")
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use pact_broker_models::contract::Contract;
+
+use crate::{openapi::OpenApi, Settings};
+
+/// Placeholder of the segments which look like an identifier.
+const ID: &str = "{id}";
+
+/// A path template, e.g. `/orders/{id}/lines`.
+#[derive(Debug, Clone)]
+struct Route {
+    template: String,
+    /// literal segments, `None` for a parameter
+    segments: Vec<Option<String>>,
+}
+
+impl Route {
+    fn new(template: &str) -> Self {
+        let segments = template
+            .split('/')
+            .map(|segment| {
+                let parameter = segment.starts_with('{') && segment.ends_with('}');
+                (!parameter).then(|| segment.to_owned())
+            })
+            .collect();
+        Self {
+            template: template.to_owned(),
+            segments,
+        }
+    }
+
+    /// Number of literal segments of the route when it matches `path`.
+    fn matches(&self, path: &[&str]) -> Option<usize> {
+        if self.segments.len() != path.len() {
+            return None;
+        }
+        let mut literals = 0;
+        for (segment, part) in self.segments.iter().zip(path) {
+            match segment {
+                Some(literal) if literal == part => literals += 1,
+                Some(_) => return None,
+                None if part.is_empty() => return None,
+                None => (),
+            }
+        }
+        Some(literals)
+    }
+}
+
+/// Turn the concrete paths of the interactions, e.g. `/orders/123`, into path templates,
+/// e.g. `/orders/{id}`, so that the requests to the same endpoint are gathered.
+///
+/// A path takes the template of the most specific route matching it, given by the user or
+/// by the OpenAPI document of the provider. Otherwise, its numeric, UUID and hexadecimal
+/// segments are replaced by `{id}`.
+#[derive(Debug, Default)]
+pub struct Normalizer {
+    routes: Vec<Route>,
+    /// routes of a single provider
+    providers: BTreeMap<String, Vec<Route>>,
+}
+
+impl Normalizer {
+    pub fn new<S: AsRef<str>>(routes: &[S]) -> Self {
+        Self {
+            routes: routes
+                .iter()
+                .map(|route| Route::new(route.as_ref()))
+                .collect(),
+            providers: BTreeMap::new(),
+        }
+    }
+
+    /// Add the routes of `provider`.
+    pub fn with_provider<I, S>(mut self, provider: &str, routes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.providers
+            .entry(provider.to_owned())
+            .or_default()
+            .extend(routes.into_iter().map(|route| Route::new(route.as_ref())));
+        self
+    }
+
+    /// The routes of `settings`, and the paths of the OpenAPI document of each provider,
+    /// `None` when there are none and `normalize-paths` is off, the paths being kept as they are.
+    pub fn load(settings: &Settings) -> Result<Option<Self>> {
+        if !settings.normalize_paths && settings.routes.is_empty() && settings.openapi.is_empty() {
+            return Ok(None);
+        }
+        let mut normalizer = Self::new(&settings.routes);
+        for (provider, path) in &settings.openapi {
+            let spec = OpenApi::read(path)?;
+            normalizer = normalizer.with_provider(provider, spec.routes());
+        }
+        Ok(Some(normalizer))
+    }
+
+    /// The template of `path`, a request to `provider`.
+    pub fn normalize(&self, provider: &str, path: &str) -> String {
+        let parts: Vec<&str> = path.split('/').collect();
+        let route = self
+            .providers
+            .get(provider)
+            .into_iter()
+            .flatten()
+            .chain(&self.routes)
+            .filter_map(|route| route.matches(&parts).map(|literals| (literals, route)))
+            // the first of the most specific routes
+            .reduce(|best, next| if next.0 > best.0 { next } else { best });
+        match route {
+            Some((_, route)) => route.template.clone(),
+            None => parts
+                .iter()
+                .map(|part| if is_identifier(part) { ID } else { part })
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+
+    /// Set the template of the path of every HTTP interaction of `contract`.
+    pub fn normalize_contract(&self, contract: &mut Contract) {
+        let provider = &contract.provider.name;
+        for interaction in contract.interactions.iter_mut().flatten() {
+            if let Some(http) = interaction.as_http_mut() {
+                http.request.route = Some(self.normalize(provider, &http.request.path));
+            }
+        }
+    }
+}
+
+/// Whether two path templates are the same route, whatever the names of their parameters,
+/// e.g. `/orders/{id}` and `/orders/{orderId}`.
+pub fn same_route(a: &str, b: &str) -> bool {
+    Route::new(a).segments == Route::new(b).segments
+}
+
+/// Whether the segment of a path is a number, a UUID or a long hexadecimal string,
+/// e.g. the id of a MongoDB document.
+fn is_identifier(segment: &str) -> bool {
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    let numeric = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+    let parts: Vec<&str> = segment.split('-').collect();
+    let uuid = parts.iter().map(|part| part.len()).eq([8, 4, 4, 4, 12])
+        && parts.iter().all(|part| is_hex(part));
+    let hex = segment.len() >= 16 && is_hex(segment) && segment.chars().any(|c| c.is_ascii_digit());
+    numeric || uuid || hex
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+    use super::Normalizer;
+    use crate::{GraphChoice, Settings};
+
+    #[test]
+    fn should_replace_identifiers() {
+        let normalizer = Normalizer::default();
+        let normalize = |path| normalizer.normalize("ms.order", path);
+
+        assert_eq!(normalize("/orders/123"), "/orders/{id}");
+        assert_eq!(
+            normalize("/orders/1c2b4b0e-5b6f-4f3c-9d1a-3f0e8c1d2a4b/lines"),
+            "/orders/{id}/lines"
+        );
+        assert_eq!(normalize("/carts/507f1f77bcf86cd799439011"), "/carts/{id}");
+        assert_eq!(normalize("/v2/orders/"), "/v2/orders/");
+        assert_eq!(normalize("/orders/facade"), "/orders/facade");
+    }
+
+    #[test]
+    fn should_prefer_the_most_specific_route() {
+        let normalizer = Normalizer::new(&["/orders/{orderId}", "/{resource}/latest"])
+            .with_provider("ms.order", ["/orders/latest", "/orders/{ref}/lines"]);
+
+        assert_eq!(
+            normalizer.normalize("ms.order", "/orders/latest"),
+            "/orders/latest"
+        );
+        assert_eq!(
+            normalizer.normalize("ms.order", "/orders/ABC-1/lines"),
+            "/orders/{ref}/lines"
+        );
+        assert_eq!(
+            normalizer.normalize("ms.stock", "/orders/latest"),
+            "/orders/{orderId}"
+        );
+        assert_eq!(
+            normalizer.normalize("ms.stock", "/orders/12/lines"),
+            "/orders/{id}/lines"
+        );
+    }
+
+    #[test]
+    fn should_only_normalize_the_paths_when_asked() {
+        let mut settings = Settings {
+            sources: vec![],
+            pact_dirs: vec![],
+            exclude: vec![],
+            groups: BTreeMap::new(),
+            routes: vec![],
+            openapi: BTreeMap::new(),
+            normalize_paths: false,
+            stale_after: Duration::from_secs(90 * 86400),
+            output: PathBuf::from("report"),
+            graph: GraphChoice::Edge,
+        };
+
+        assert!(Normalizer::load(&settings).unwrap().is_none());
+
+        settings.normalize_paths = true;
+        let normalizer = Normalizer::load(&settings).unwrap().unwrap();
+        assert_eq!(
+            normalizer.normalize("ms.order", "/orders/123"),
+            "/orders/{id}"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use log::{error, info, warn};
+use pact_broker_api::client::BrokerClient;
+use serde::Serialize;
+
+use crate::{
+    build_graph,
+    cli::ServeArgs,
+    fetch_sources,
+    orphans::{self, Orphans},
+    reporter,
+    stale::{self, Staleness},
+    watch::PactWatcher,
+    GraphChoice, Settings,
+};
+
+const JSON_FILE: &str = "graph.json";
+/// Route returning the generation of the snapshot, polled by the pages to live-reload.
+const GENERATION_PATH: &str = "generation";
+
+/// Rendered pages, replaced at each refresh.
+#[derive(Debug, Default)]
+struct Snapshot {
+    generation: u64,
+    index: String,
+    json: String,
+    pages: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Index {
+    charts: Vec<Chart>,
+    json_href: &'static str,
+    refreshed_at: String,
+    live_reload: bool,
+    stale: Staleness,
+    orphans: Orphans,
+}
+
+#[derive(Debug, Serialize)]
+struct Chart {
+    name: String,
+    href: String,
+}
+
+type State = Arc<RwLock<Option<Arc<Snapshot>>>>;
+
+/// Serve the charts, fetching the contracts every `args.refresh`,
+/// and whenever a pact file changed when a `watcher` is given.
+/// The pages then reload themselves when the graph changed.
+pub async fn serve(
+    settings: Settings,
+    clients: Vec<BrokerClient>,
+    args: ServeArgs,
+    mut watcher: Option<PactWatcher>,
+) -> Result<()> {
+    let state = State::default();
+
+    let refresher = {
+        let state = state.clone();
+        let live_reload = watcher.is_some();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(args.refresh);
+            loop {
+                match &mut watcher {
+                    Some(watcher) => tokio::select! {
+                        _ = interval.tick() => (),
+                        _ = watcher.changed() => info!("Pact files changed"),
+                    },
+                    None => {
+                        interval.tick().await;
+                    }
+                }
+                info!("Fetching contracts");
+                match snapshot(&settings, &clients, live_reload).await {
+                    Ok(mut snapshot) => {
+                        let mut current = state.write().unwrap();
+                        // the pages only reload when the graph changed
+                        snapshot.generation = match current.as_deref() {
+                            Some(previous) if previous.json == snapshot.json => previous.generation,
+                            Some(previous) => previous.generation + 1,
+                            None => 0,
+                        };
+                        *current = Some(Arc::new(snapshot));
+                    }
+                    Err(e) => error!("Could not refresh the graph: {:#}", e),
+                }
+            }
+        })
+    };
+
+    let addr = SocketAddr::new(args.bind, args.port);
+    let make_service = make_service_fn(move |_| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+    });
+    let server = Server::try_bind(&addr)?.serve(make_service);
+    println!("Serving the charts on http://{addr}");
+
+    server
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    refresher.abort();
+    Ok(())
+}
+
+async fn snapshot(
+    settings: &Settings,
+    clients: &[BrokerClient],
+    live_reload: bool,
+) -> Result<Snapshot> {
+    let sources = fetch_sources(settings, clients).await?;
+    let statuses = stale::statuses(&sources).await;
+    let graph = build_graph(settings, &sources).with_verifications(&statuses);
+    let stale = Staleness::new(&statuses, settings.stale_after, chrono::Utc::now());
+    // the pacticipants are not needed by the charts, whose refresh must not fail without them
+    let pacticipants = orphans::fetch_pacticipants(clients)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Could not fetch the pacticipants: {e:#}");
+            vec![]
+        });
+    let orphans = Orphans::new(&sources, &pacticipants, &statuses, &settings.exclude);
+    let json = serde_json::to_string(&graph)?;
+
+    let mut pages = HashMap::new();
+    let mut charts = vec![];
+    for choice in GraphChoice::value_variants() {
+        let page =
+            reporter::render(*choice, json.clone(), live_reload).map_err(|e| anyhow!("{}", e))?;
+        let href = choice.file_name();
+        charts.push(Chart {
+            name: href.trim_end_matches(".html").to_owned(),
+            href: href.clone(),
+        });
+        pages.insert(href, page);
+    }
+
+    let index = reporter::render_index(&Index {
+        charts,
+        json_href: JSON_FILE,
+        refreshed_at: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        live_reload,
+        stale,
+        orphans,
+    })
+    .map_err(|e| anyhow!("{}", e))?;
+
+    Ok(Snapshot {
+        generation: 0,
+        index,
+        json,
+        pages,
+    })
+}
+
+async fn handle(state: State, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET {
+        return Ok(reply(StatusCode::METHOD_NOT_ALLOWED, "text/plain", ""));
+    }
+
+    let snapshot = state.read().unwrap().clone();
+    let Some(snapshot) = snapshot else {
+        return Ok(reply(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "text/plain",
+            "The contracts are being fetched, try again in a few seconds",
+        ));
+    };
+
+    let response = match req.uri().path().trim_start_matches('/') {
+        "" | "index.html" => reply(StatusCode::OK, "text/html", &snapshot.index),
+        JSON_FILE => reply(StatusCode::OK, "application/json", &snapshot.json),
+        GENERATION_PATH => reply(
+            StatusCode::OK,
+            "text/plain",
+            &snapshot.generation.to_string(),
+        ),
+        path => match snapshot.pages.get(path) {
+            Some(page) => reply(StatusCode::OK, "text/html", page),
+            None => reply(StatusCode::NOT_FOUND, "text/plain", "Not found"),
+        },
+    };
+    Ok(response)
+}
+
+fn reply(status: StatusCode, content_type: &str, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(body.to_owned()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use hyper::{Body, Method, Request, StatusCode};
+
+    use super::{handle, Snapshot, State};
+
+    async fn get(state: &State, path: &str) -> StatusCode {
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(path)
+            .body(Body::empty())
+            .unwrap();
+        handle(state.clone(), req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn should_be_unavailable_before_first_fetch() {
+        let state = State::default();
+        assert_eq!(get(&state, "/").await, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn should_serve_the_pages() {
+        let state = State::default();
+        *state.write().unwrap() = Some(Arc::new(Snapshot {
+            generation: 1,
+            index: "index".to_owned(),
+            json: "{}".to_owned(),
+            pages: HashMap::from([("edge-bundling.html".to_owned(), "chart".to_owned())]),
+        }));
+        assert_eq!(get(&state, "/").await, StatusCode::OK);
+        assert_eq!(get(&state, "/graph.json").await, StatusCode::OK);
+        assert_eq!(get(&state, "/generation").await, StatusCode::OK);
+        assert_eq!(get(&state, "/edge-bundling.html").await, StatusCode::OK);
+        assert_eq!(get(&state, "/unknown.html").await, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,290 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, stream, FutureExt, StreamExt};
+use log::warn;
+use pact_broker_api::client::{BrokerClient, MAX_CONCURRENT_REQUESTS};
+use pact_broker_models::contract::Contract;
+use serde::Serialize;
+
+use crate::{fetch_sources, ContractSource, Settings};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// The latest pact between a consumer and a provider, and whether the provider verified it.
+#[derive(Debug)]
+pub struct PactStatus {
+    /// source of the pact when several are merged, as the broker of the links of the graph
+    pub(crate) broker: Option<String>,
+    pub(crate) consumer: String,
+    pub(crate) provider: String,
+    pub(crate) created_at: Option<String>,
+    /// `None` when unknown, e.g. for a local pact file
+    pub(crate) verification: Option<VerificationStatus>,
+}
+
+/// Result of the latest verification of a pact by its provider.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerificationStatus {
+    Success,
+    Failure,
+    /// the provider never verified the pact
+    Unverified,
+}
+
+/// Contracts which look abandoned.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct Staleness {
+    /// age in days from which a pact is stale
+    pub threshold: u64,
+    /// pacts older than the threshold, the oldest first
+    pub pacts: Vec<StalePact>,
+    /// consumers whose every pact is older than the threshold
+    pub consumers: Vec<StalePact>,
+    /// pacts never verified by their provider
+    pub unverified: Vec<StalePact>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct StalePact {
+    pub consumer: String,
+    /// `None` for a consumer
+    pub provider: Option<String>,
+    pub created_at: Option<String>,
+    /// age in days
+    pub age: Option<u64>,
+}
+
+/// Age of a contract created at the RFC 3339 date `created_at`, e.g. `2023-01-10T10:00:00+00:00`.
+pub fn age(created_at: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let created_at = DateTime::parse_from_rfc3339(created_at).ok()?;
+    now.signed_duration_since(created_at).to_std().ok()
+}
+
+impl Staleness {
+    pub fn new(pacts: &[PactStatus], threshold: Duration, now: DateTime<Utc>) -> Self {
+        let days = |age: Duration| age.as_secs() / DAY;
+        let age_of = |pact: &PactStatus| {
+            pact.created_at
+                .as_deref()
+                .and_then(|created_at| age(created_at, now))
+        };
+        let stale_pact = |pact: &PactStatus, provider: bool| StalePact {
+            consumer: pact.consumer.clone(),
+            provider: provider.then(|| pact.provider.clone()),
+            created_at: pact.created_at.clone(),
+            age: age_of(pact).map(days),
+        };
+
+        let mut staleness = Self {
+            threshold: days(threshold),
+            ..Self::default()
+        };
+        // latest dated pact of each consumer
+        let mut latest = BTreeMap::<&str, &PactStatus>::new();
+        for pact in pacts {
+            if age_of(pact).is_some_and(|age| age > threshold) {
+                staleness.pacts.push(stale_pact(pact, true));
+            }
+            if pact.verification == Some(VerificationStatus::Unverified) {
+                staleness.unverified.push(stale_pact(pact, true));
+            }
+            let Some(age) = age_of(pact) else {
+                continue;
+            };
+            latest
+                .entry(&pact.consumer)
+                .and_modify(|current| {
+                    if age_of(current).is_some_and(|current| age < current) {
+                        *current = pact;
+                    }
+                })
+                .or_insert(pact);
+        }
+        staleness.consumers = latest
+            .values()
+            .map(|pact| stale_pact(pact, false))
+            .filter(|consumer| consumer.age.is_some_and(|age| age > days(threshold)))
+            .collect();
+
+        for list in [&mut staleness.pacts, &mut staleness.consumers] {
+            list.sort_by_key(|pact| std::cmp::Reverse(pact.age));
+        }
+        staleness
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pacts.is_empty() && self.consumers.is_empty() && self.unverified.is_empty()
+    }
+}
+
+/// Fetch the contracts of `settings` and the verification status of those of the brokers.
+pub async fn check(settings: &Settings, clients: &[BrokerClient]) -> Result<Staleness> {
+    let sources = fetch_sources(settings, clients).await?;
+    let pacts = statuses(&sources).await;
+    Ok(Staleness::new(&pacts, settings.stale_after, Utc::now()))
+}
+
+/// The status of the contracts of each source, the verifications of the contracts of the
+/// brokers being fetched [`MAX_CONCURRENT_REQUESTS`] at a time, as
+/// [`BrokerClient::batch_get`] does. A verification which could not be fetched is unknown.
+pub fn statuses(sources: &[ContractSource]) -> BoxFuture<'_, Vec<PactStatus>> {
+    let tagged = sources.len() > 1;
+    let contracts = sources.iter().flat_map(|source| {
+        source
+            .contracts
+            .iter()
+            .map(move |contract| (source, contract))
+    });
+    // boxed so that the closures of the stream are Send for any lifetime, e.g. in the server
+    stream::iter(contracts)
+        .map(move |(source, contract)| status(source, contract, tagged))
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .boxed()
+}
+
+async fn status(source: &ContractSource, contract: &Contract, tagged: bool) -> PactStatus {
+    PactStatus {
+        broker: tagged.then(|| source.name.clone()),
+        consumer: contract.consumer.name.clone(),
+        provider: contract.provider.name.clone(),
+        created_at: contract.created_at.clone(),
+        verification: verification(source.client.as_ref(), contract).await,
+    }
+}
+
+/// The latest verification of `contract`, `None` when it was not fetched from a broker,
+/// or when it could not be fetched.
+async fn verification(
+    api: Option<&BrokerClient>,
+    contract: &Contract,
+) -> Option<VerificationStatus> {
+    let api = api?;
+    contract.link("pb:latest-verification-results")?;
+    match api.pacts().latest_verification(contract).await {
+        Ok(Some(verification)) if verification.success => Some(VerificationStatus::Success),
+        Ok(Some(_)) => Some(VerificationStatus::Failure),
+        Ok(None) => Some(VerificationStatus::Unverified),
+        Err(e) => {
+            warn!(
+                "Could not fetch the verification of the pact between {} and {}: {}",
+                contract.consumer.name, contract.provider.name, e
+            );
+            None
+        }
+    }
+}
+
+/// Print the stale pacts, the consumers which stopped publishing and the unverified pacts.
+pub async fn report(settings: &Settings, clients: &[BrokerClient]) -> Result<()> {
+    let staleness = check(settings, clients).await?;
+    print!("{}", render(&staleness));
+    Ok(())
+}
+
+fn render(staleness: &Staleness) -> String {
+    let describe = |pact: &StalePact| {
+        let name = match &pact.provider {
+            Some(provider) => format!("{} -> {}", pact.consumer, provider),
+            None => pact.consumer.clone(),
+        };
+        match (&pact.created_at, pact.age) {
+            (Some(created_at), Some(age)) => format!("{name}  {created_at} ({age} days)"),
+            _ => name,
+        }
+    };
+
+    let mut out = String::new();
+    if staleness.is_empty() {
+        out.push_str("No stale contract\n");
+    }
+    for (title, pacts) in [
+        (
+            format!("Pacts older than {} days:", staleness.threshold),
+            &staleness.pacts,
+        ),
+        (
+            format!(
+                "Consumers without pact published for {} days:",
+                staleness.threshold
+            ),
+            &staleness.consumers,
+        ),
+        (
+            "Pacts never verified by their provider:".to_owned(),
+            &staleness.unverified,
+        ),
+    ] {
+        if pacts.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "{title}");
+        for pact in pacts {
+            let _ = writeln!(out, "  {}", describe(pact));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{render, PactStatus, Staleness, VerificationStatus};
+
+    #[test]
+    fn should_list_stale_and_unverified_pacts() {
+        let pact = |consumer: &str, provider: &str, created_at: &str, verification| PactStatus {
+            broker: None,
+            consumer: consumer.to_owned(),
+            provider: provider.to_owned(),
+            created_at: Some(created_at.to_owned()),
+            verification,
+        };
+        let pacts = [
+            pact(
+                "ms.billing",
+                "ms.order",
+                "2023-01-01T10:00:00+00:00",
+                Some(VerificationStatus::Success),
+            ),
+            pact(
+                "ms.billing",
+                "ms.stock",
+                "2023-03-20T10:00:00+00:00",
+                Some(VerificationStatus::Unverified),
+            ),
+            pact("ms.legacy", "ms.order", "2022-06-01T10:00:00Z", None),
+            // an undated pact does not tell when the consumer published
+            PactStatus {
+                broker: None,
+                consumer: "ms.legacy".to_owned(),
+                provider: "ms.stock".to_owned(),
+                created_at: None,
+                verification: None,
+            },
+        ];
+        let now: DateTime<Utc> = "2023-04-01T00:00:00Z".parse().unwrap();
+
+        let staleness = Staleness::new(&pacts, Duration::from_secs(30 * 86400), now);
+
+        assert_eq!(
+            render(&staleness),
+            "Pacts older than 30 days:\n\
+             \x20 ms.legacy -> ms.order  2022-06-01T10:00:00Z (303 days)\n\
+             \x20 ms.billing -> ms.order  2023-01-01T10:00:00+00:00 (89 days)\n\
+             Consumers without pact published for 30 days:\n\
+             \x20 ms.legacy  2022-06-01T10:00:00Z (303 days)\n\
+             Pacts never verified by their provider:\n\
+             \x20 ms.billing -> ms.stock  2023-03-20T10:00:00+00:00 (11 days)\n"
+        );
+    }
+}
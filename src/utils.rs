@@ -9,3 +9,49 @@ pub mod fs {
         stdFs::write(path, content)
     }
 }
+
+pub mod pattern {
+    /// Whether `name` matches `pattern`, where `*` stands for any sequence of characters.
+    pub fn matches(pattern: &str, name: &str) -> bool {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = name.strip_prefix(first) else {
+            return false;
+        };
+        let mut parts: Vec<&str> = parts.collect();
+        let Some(last) = parts.pop() else {
+            return rest.is_empty();
+        };
+        for part in parts {
+            match rest.find(part) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        rest.len() >= last.len() && rest.ends_with(last)
+    }
+
+    /// Whether `name` matches any of `patterns`.
+    pub fn matches_any<S: AsRef<str>>(patterns: &[S], name: &str) -> bool {
+        patterns
+            .iter()
+            .any(|pattern| matches(pattern.as_ref(), name))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::matches;
+
+        #[test]
+        fn should_match_patterns() {
+            assert!(matches("ms.cart", "ms.cart"));
+            assert!(!matches("ms.cart", "ms.cart-api"));
+            assert!(matches("ms.cart*", "ms.cart-api"));
+            assert!(matches("*-api", "ms.cart-api"));
+            assert!(matches("ms.*-api", "ms.cart-api"));
+            assert!(!matches("ms.*-api", "ms.cart-worker"));
+            assert!(matches("*", "anything"));
+            assert!(!matches("a*a", "a"));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use log::{debug, error};
+use notify::{event::EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::local;
+
+/// Delay during which successive changes are gathered into a single notification,
+/// as writing a pact file usually triggers several events.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the pact files of some directories.
+pub struct PactWatcher {
+    // dropping the watcher stops watching
+    _watcher: RecommendedWatcher,
+    changes: mpsc::Receiver<PathBuf>,
+}
+
+impl PactWatcher {
+    pub fn new(dirs: &[PathBuf]) -> Result<Self> {
+        let (tx, changes) = mpsc::channel(64);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                match event {
+                    Ok(event) => {
+                        for path in pact_files(event) {
+                            // the receiver only needs to know something changed
+                            let _ = tx.try_send(path);
+                        }
+                    }
+                    Err(e) => error!("Could not watch the pact files: {}", e),
+                }
+            })?;
+        for dir in dirs {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
+        }
+        Ok(Self {
+            _watcher: watcher,
+            changes,
+        })
+    }
+
+    /// Wait until a pact file changed.
+    pub async fn changed(&mut self) {
+        if let Some(path) = self.changes.recv().await {
+            debug!("{} changed", path.display());
+        }
+        tokio::time::sleep(DEBOUNCE).await;
+        while let Ok(path) = self.changes.try_recv() {
+            debug!("{} changed", path.display());
+        }
+    }
+}
+
+/// The pact files modified by `event`, none when they were only read.
+fn pact_files(event: notify::Event) -> Vec<PathBuf> {
+    if let EventKind::Access(_) = event.kind {
+        return vec![];
+    }
+    event
+        .paths
+        .into_iter()
+        .filter(|path| local::is_pact_file(path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use notify::{
+        event::{AccessKind, CreateKind},
+        Event, EventKind,
+    };
+
+    use super::{pact_files, PactWatcher};
+
+    #[test]
+    fn should_only_notify_the_modified_pact_files() {
+        let created = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(PathBuf::from("pacts/front-gateway.json"))
+            .add_path(PathBuf::from("pacts/front-gateway.json.tmp"));
+        let read = Event::new(EventKind::Access(AccessKind::Any))
+            .add_path(PathBuf::from("pacts/front-gateway.json"));
+
+        assert_eq!(
+            pact_files(created),
+            vec![PathBuf::from("pacts/front-gateway.json")]
+        );
+        assert!(pact_files(read).is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_gather_successive_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = PactWatcher::new(&[dir.path().to_path_buf()]).unwrap();
+
+        for name in ["a.json", "b.json", "notes.txt"] {
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        tokio::time::timeout(Duration::from_secs(5), watcher.changed())
+            .await
+            .expect("no change notified");
+
+        // the changes written before the end of the debounce were all consumed
+        assert!(watcher.changes.try_recv().is_err());
+    }
+}
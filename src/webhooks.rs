@@ -0,0 +1,196 @@
+// Copyright 2022 ManoMano Colibri SAS.
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use futures::{stream, StreamExt, TryStreamExt};
+use pact_broker_api::client::{self, BrokerClient, MAX_CONCURRENT_REQUESTS};
+use pact_broker_models::{
+    contract::Contract,
+    webhooks::{TriggeredStatus, TriggeredWebhook, Webhook},
+};
+
+use crate::{fetch_contracts, utils::pattern, Settings};
+
+/// Webhooks of the pact between a consumer and a provider.
+#[derive(Debug)]
+struct Edge {
+    consumer: String,
+    provider: String,
+    broker: Option<String>,
+    webhooks: Vec<WebhookStatus>,
+}
+
+#[derive(Debug)]
+struct WebhookStatus {
+    name: String,
+    enabled: bool,
+    events: Vec<String>,
+    /// status and date of the last execution, triggered by the latest pact
+    last: Option<(TriggeredStatus, Option<String>)>,
+}
+
+/// Print, for each pact of the brokers, the webhooks it triggers
+/// and whether their last executions succeeded.
+pub async fn report(settings: &Settings, clients: &[BrokerClient]) -> Result<()> {
+    let tagged = clients.len() > 1;
+    let mut edges = vec![];
+    for (source, api) in settings.sources.iter().zip(clients) {
+        let contracts: Vec<Contract> = fetch_contracts(api)
+            .await?
+            .into_iter()
+            .filter(|contract| {
+                !pattern::matches_any(&settings.exclude, &contract.consumer.name)
+                    && !pattern::matches_any(&settings.exclude, &contract.provider.name)
+            })
+            .collect();
+        let broker = tagged.then(|| source.name.clone());
+        let found: Vec<Edge> = stream::iter(&contracts)
+            .map(|contract| edge(api, contract, broker.clone()))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        edges.extend(found);
+    }
+    edges.sort_by(|a, b| (&a.consumer, &a.provider).cmp(&(&b.consumer, &b.provider)));
+    print!("{}", render(&edges));
+    Ok(())
+}
+
+async fn edge(api: &BrokerClient, contract: &Contract, broker: Option<String>) -> Result<Edge> {
+    let webhooks = or_none(api.webhooks().for_pact(contract).await)?;
+    let triggered = or_none(api.webhooks().triggered(contract).await)?;
+    Ok(Edge {
+        consumer: contract.consumer.name.clone(),
+        provider: contract.provider.name.clone(),
+        broker,
+        webhooks: webhooks
+            .iter()
+            .map(|webhook| status(webhook, &triggered))
+            .collect(),
+    })
+}
+
+/// No webhook when the broker does not support them, or has none for the pact.
+fn or_none<T>(result: client::Result<Vec<T>>) -> client::Result<Vec<T>> {
+    match result {
+        Err(client::Error::Unsupported { .. } | client::Error::NotFound { .. }) => Ok(vec![]),
+        result => result,
+    }
+}
+
+fn status(webhook: &Webhook, triggered: &[TriggeredWebhook]) -> WebhookStatus {
+    let href = webhook.links.get("self").map(|link| link.href.as_str());
+    let last = triggered
+        .iter()
+        .filter(|execution| execution.webhook().map(|link| link.href.as_str()) == href)
+        .max_by(|a, b| a.triggered_at.cmp(&b.triggered_at))
+        .map(|execution| (execution.status, execution.triggered_at.clone()));
+    WebhookStatus {
+        name: webhook.name(),
+        enabled: webhook.is_enabled(),
+        events: webhook
+            .events
+            .iter()
+            .map(|event| event.name.clone())
+            .collect(),
+        last,
+    }
+}
+
+fn render(edges: &[Edge]) -> String {
+    let mut out = String::new();
+    let mut failing = 0;
+    for edge in edges {
+        let _ = write!(out, "{} -> {}", edge.consumer, edge.provider);
+        if let Some(broker) = &edge.broker {
+            let _ = write!(out, " ({broker})");
+        }
+        out.push('\n');
+        if edge.webhooks.is_empty() {
+            out.push_str("  no webhook\n");
+        }
+        for webhook in &edge.webhooks {
+            let last = match &webhook.last {
+                _ if !webhook.enabled => "disabled".to_owned(),
+                None => "never triggered".to_owned(),
+                Some((status, at)) => {
+                    if *status == TriggeredStatus::Failure {
+                        failing += 1;
+                    }
+                    let status = match status {
+                        TriggeredStatus::NotRun => "not run",
+                        TriggeredStatus::Retrying => "retrying",
+                        TriggeredStatus::Success => "success",
+                        TriggeredStatus::Failure => "FAILURE",
+                        TriggeredStatus::Unknown => "unknown",
+                    };
+                    match at {
+                        Some(at) => format!("{status} at {at}"),
+                        None => status.to_owned(),
+                    }
+                }
+            };
+            let _ = writeln!(
+                out,
+                "  {} [{}]: {}",
+                webhook.name,
+                webhook.events.join(", "),
+                last
+            );
+        }
+    }
+    if failing > 0 {
+        let _ = writeln!(out, "{failing} webhook(s) failed on their last execution");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pact_broker_models::webhooks::TriggeredStatus;
+
+    use super::{render, Edge, WebhookStatus};
+
+    #[test]
+    fn should_render_the_last_executions() {
+        let edges = vec![
+            Edge {
+                consumer: "ms.billing".to_owned(),
+                provider: "ms.order".to_owned(),
+                broker: None,
+                webhooks: vec![
+                    WebhookStatus {
+                        name: "Build".to_owned(),
+                        enabled: true,
+                        events: vec!["contract_content_changed".to_owned()],
+                        last: Some((TriggeredStatus::Failure, Some("2023-01-10".to_owned()))),
+                    },
+                    WebhookStatus {
+                        name: "Notify".to_owned(),
+                        enabled: false,
+                        events: vec![],
+                        last: None,
+                    },
+                ],
+            },
+            Edge {
+                consumer: "ms.shipping".to_owned(),
+                provider: "ms.order".to_owned(),
+                broker: Some("prod".to_owned()),
+                webhooks: vec![],
+            },
+        ];
+
+        assert_eq!(
+            render(&edges),
+            "ms.billing -> ms.order\n\
+             \x20 Build [contract_content_changed]: FAILURE at 2023-01-10\n\
+             \x20 Notify []: disabled\n\
+             ms.shipping -> ms.order (prod)\n\
+             \x20 no webhook\n\
+             1 webhook(s) failed on their last execution\n"
+        );
+    }
+}